@@ -1,8 +1,14 @@
+import functools
+import hashlib
+import hmac
+import math
+import os
 import sys
 from PyQt6.QtWidgets import (QApplication, QMainWindow, QWidget, QVBoxLayout, 
                             QHBoxLayout, QLabel, QTextEdit, QLineEdit, 
                             QPushButton, QSpinBox, QTabWidget, QGraphicsScene, 
-                            QGraphicsView, QSplitter, QGridLayout, QToolBar)
+                            QGraphicsView, QSplitter, QGridLayout, QToolBar,
+                            QComboBox, QFileDialog, QTableWidget, QTableWidgetItem)
 from PyQt6.QtCore import Qt, QRectF, QPoint
 from PyQt6.QtGui import QFont, QPen, QBrush, QColor, QPainter, QAction, QIcon
 
@@ -21,6 +27,10 @@ def vec_xor(vec1, vec2):
     
     return result
 
+def vec_and(vec1, vec2):
+    """Выполняет побитовую операцию AND между элементами двух векторов одинаковой длины"""
+    return [v1 & v2 for v1, v2 in zip(vec1, vec2)]
+
 def vec_invert(vect):
     """
     Выполняет побитовую инверсию каждого элемента вектора.
@@ -78,7 +88,645 @@ def f(right, key):
     # Последовательно применяем XOR с ключом, инверсию и побитовый сдвиг влево
     return bit_left(vec_invert(vec_xor(right, key)))
 
-def keys_gen(key, decrypt, rounds):
+def fit_key(key, width):
+    """
+    Приводит ключ к заданной ширине в байтах.
+    
+    Длинный ключ сжимается: его части шириной width складываются по XOR.
+    Короткий ключ расширяется циклическим повторением.
+    
+    Args:
+        key: Исходный вектор байтов ключа
+        width: Требуемая ширина в байтах
+    
+    Returns:
+        Новый вектор ключа длиной ровно width байтов
+    """
+    if not key:
+        raise ValueError("Ключ не может быть пустым")
+    if width <= 0:
+        raise ValueError("Ширина ключа раунда должна быть положительной")
+    
+    result = [key[i % len(key)] for i in range(width)]  # Расширение повторением
+    for i in range(width, len(key)):
+        result[i % width] ^= key[i]  # Сжатие сложением по XOR
+    return result
+
+def vec_to_int(vect):
+    """Интерпретирует вектор байтов как целое число (big-endian)"""
+    return int.from_bytes(bytes(vect), 'big')
+
+def int_to_vec(value, size):
+    """Преобразует целое число в вектор из size байтов (big-endian)"""
+    return list(value.to_bytes(size, 'big'))
+
+def vec_to_bits(vect):
+    """Раскладывает вектор байтов в список битов, старший бит первым"""
+    return [(x >> (7 - i)) & 1 for x in vect for i in range(8)]
+
+def bits_to_vec(bits):
+    """Собирает список битов (старший бит первым) обратно в вектор байтов"""
+    result = []
+    for i in range(0, len(bits), 8):
+        byte = 0
+        for bit in bits[i:i + 8]:
+            byte = (byte << 1) | bit
+        result.append(byte)
+    return result
+
+def rotl(value, shift, bits):
+    """Циклический сдвиг числа разрядности bits влево на shift битов"""
+    shift %= bits
+    mask = (1 << bits) - 1
+    return ((value << shift) | (value >> (bits - shift))) & mask
+
+def vec_rotl(vect, shift):
+    """
+    Циклически сдвигает весь вектор влево на shift битов.
+    
+    В отличие от bit_left биты переходят между байтами и не теряются.
+    Отрицательный shift означает сдвиг вправо.
+    """
+    return int_to_vec(rotl(vec_to_int(vect), shift, 8 * len(vect)), len(vect))
+
+def vec_rotr(vect, shift):
+    """Циклически сдвигает весь вектор вправо на shift битов"""
+    return vec_rotl(vect, -shift)
+
+# S-блок шифра PRESENT (4 бита -> 4 бита)
+PRESENT_SBOX = [0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2]
+
+# Узлы замены id-tc26-gost-28147-param-Z из ГОСТ Р 34.12-2015 (π0..π7)
+GOST_SBOX_Z = [
+    [12, 4, 6, 2, 10, 5, 11, 9, 14, 8, 13, 7, 0, 3, 15, 1],
+    [6, 8, 2, 3, 9, 10, 5, 12, 1, 14, 4, 7, 11, 13, 0, 15],
+    [11, 3, 5, 8, 2, 15, 10, 13, 14, 1, 7, 4, 12, 9, 6, 0],
+    [12, 8, 2, 1, 13, 4, 15, 6, 7, 0, 10, 5, 3, 14, 9, 11],
+    [7, 15, 5, 10, 8, 1, 6, 13, 0, 9, 3, 14, 11, 4, 2, 12],
+    [5, 13, 15, 6, 9, 2, 12, 10, 11, 7, 8, 1, 4, 3, 14, 0],
+    [8, 14, 2, 5, 6, 9, 1, 12, 15, 4, 11, 0, 13, 10, 3, 7],
+    [1, 7, 14, 13, 0, 5, 8, 3, 4, 15, 10, 6, 9, 12, 11, 2],
+]
+
+# Узлы замены ГОСТ 28147-89 из RFC 4357 (π0..π7, тетрада 0 - младшая)
+GOST_SBOX_TEST = [
+    [4, 2, 15, 5, 9, 1, 0, 8, 14, 3, 11, 12, 13, 7, 10, 6],
+    [12, 9, 15, 14, 8, 1, 3, 10, 2, 7, 4, 13, 6, 0, 11, 5],
+    [13, 8, 14, 12, 7, 3, 9, 10, 1, 5, 2, 4, 6, 15, 0, 11],
+    [14, 9, 11, 2, 5, 15, 7, 1, 0, 13, 12, 6, 10, 4, 3, 8],
+    [3, 14, 5, 9, 6, 8, 0, 13, 10, 11, 7, 12, 2, 1, 15, 4],
+    [8, 15, 6, 11, 1, 9, 12, 5, 13, 3, 7, 10, 0, 14, 2, 4],
+    [9, 11, 12, 0, 3, 6, 7, 5, 4, 8, 14, 15, 1, 10, 2, 13],
+    [12, 6, 5, 2, 11, 0, 9, 13, 3, 14, 7, 10, 15, 4, 1, 8],
+]
+
+GOST_SBOX_CRYPTOPRO_A = [
+    [9, 6, 3, 2, 8, 11, 1, 7, 10, 4, 14, 15, 12, 0, 13, 5],
+    [3, 7, 14, 9, 8, 10, 15, 0, 5, 2, 6, 12, 11, 4, 13, 1],
+    [14, 4, 6, 2, 11, 3, 13, 8, 12, 15, 5, 10, 0, 7, 1, 9],
+    [14, 7, 10, 12, 13, 1, 3, 9, 0, 2, 11, 4, 15, 8, 5, 6],
+    [11, 5, 1, 9, 8, 13, 15, 0, 14, 4, 2, 3, 12, 7, 10, 6],
+    [3, 10, 13, 12, 1, 2, 0, 11, 7, 5, 9, 4, 8, 15, 14, 6],
+    [1, 13, 2, 9, 7, 10, 6, 0, 8, 12, 4, 5, 15, 3, 11, 14],
+    [11, 10, 15, 5, 0, 12, 14, 8, 6, 2, 3, 9, 1, 7, 13, 4],
+]
+
+GOST_SBOX_CRYPTOPRO_B = [
+    [8, 4, 11, 1, 3, 5, 0, 9, 2, 14, 10, 12, 13, 6, 7, 15],
+    [0, 1, 2, 10, 4, 13, 5, 12, 9, 7, 3, 15, 11, 8, 6, 14],
+    [14, 12, 0, 10, 9, 2, 13, 11, 7, 5, 8, 15, 3, 6, 1, 4],
+    [7, 5, 0, 13, 11, 6, 1, 2, 3, 10, 12, 15, 4, 14, 9, 8],
+    [2, 7, 12, 15, 9, 5, 10, 11, 1, 4, 0, 13, 6, 8, 14, 3],
+    [8, 3, 2, 6, 4, 13, 14, 11, 12, 1, 7, 15, 10, 0, 9, 5],
+    [5, 2, 10, 11, 9, 1, 12, 3, 7, 4, 13, 0, 6, 15, 8, 14],
+    [0, 4, 11, 14, 8, 3, 7, 1, 10, 2, 9, 6, 15, 13, 5, 12],
+]
+
+GOST_SBOX_CRYPTOPRO_C = [
+    [1, 11, 12, 2, 9, 13, 0, 15, 4, 5, 8, 14, 10, 7, 6, 3],
+    [0, 1, 7, 13, 11, 4, 5, 2, 8, 14, 15, 12, 9, 10, 6, 3],
+    [8, 2, 5, 0, 4, 9, 15, 10, 3, 7, 12, 13, 6, 14, 1, 11],
+    [3, 6, 0, 1, 5, 13, 10, 8, 11, 2, 9, 7, 14, 15, 12, 4],
+    [8, 13, 11, 0, 4, 5, 1, 2, 9, 3, 12, 14, 6, 15, 10, 7],
+    [12, 9, 11, 1, 8, 14, 2, 4, 7, 3, 6, 5, 10, 0, 15, 13],
+    [10, 9, 6, 8, 13, 14, 2, 0, 15, 3, 5, 11, 4, 1, 12, 7],
+    [7, 4, 0, 5, 10, 2, 15, 14, 12, 6, 1, 11, 13, 9, 3, 8],
+]
+
+GOST_SBOX_CRYPTOPRO_D = [
+    [15, 12, 2, 10, 6, 4, 5, 0, 7, 9, 14, 13, 1, 11, 8, 3],
+    [11, 6, 3, 4, 12, 15, 14, 2, 7, 13, 8, 0, 5, 10, 9, 1],
+    [1, 12, 11, 0, 15, 14, 6, 5, 10, 13, 4, 8, 9, 3, 7, 2],
+    [1, 5, 14, 12, 10, 7, 0, 13, 6, 2, 11, 4, 9, 3, 15, 8],
+    [0, 12, 8, 9, 13, 2, 10, 11, 7, 3, 6, 5, 4, 14, 15, 1],
+    [8, 0, 15, 3, 2, 5, 14, 11, 1, 10, 4, 7, 12, 9, 13, 6],
+    [3, 0, 6, 15, 1, 14, 9, 2, 13, 8, 12, 4, 11, 10, 5, 7],
+    [1, 10, 6, 8, 15, 11, 0, 4, 12, 3, 5, 9, 7, 13, 2, 14],
+]
+
+# S-блоки DES S1..S8: 4 строки по 16 значений
+DES_SBOXES = [
+    [[14, 4, 13, 1, 2, 15, 11, 8, 3, 10, 6, 12, 5, 9, 0, 7],
+     [0, 15, 7, 4, 14, 2, 13, 1, 10, 6, 12, 11, 9, 5, 3, 8],
+     [4, 1, 14, 8, 13, 6, 2, 11, 15, 12, 9, 7, 3, 10, 5, 0],
+     [15, 12, 8, 2, 4, 9, 1, 7, 5, 11, 3, 14, 10, 0, 6, 13]],
+    [[15, 1, 8, 14, 6, 11, 3, 4, 9, 7, 2, 13, 12, 0, 5, 10],
+     [3, 13, 4, 7, 15, 2, 8, 14, 12, 0, 1, 10, 6, 9, 11, 5],
+     [0, 14, 7, 11, 10, 4, 13, 1, 5, 8, 12, 6, 9, 3, 2, 15],
+     [13, 8, 10, 1, 3, 15, 4, 2, 11, 6, 7, 12, 0, 5, 14, 9]],
+    [[10, 0, 9, 14, 6, 3, 15, 5, 1, 13, 12, 7, 11, 4, 2, 8],
+     [13, 7, 0, 9, 3, 4, 6, 10, 2, 8, 5, 14, 12, 11, 15, 1],
+     [13, 6, 4, 9, 8, 15, 3, 0, 11, 1, 2, 12, 5, 10, 14, 7],
+     [1, 10, 13, 0, 6, 9, 8, 7, 4, 15, 14, 3, 11, 5, 2, 12]],
+    [[7, 13, 14, 3, 0, 6, 9, 10, 1, 2, 8, 5, 11, 12, 4, 15],
+     [13, 8, 11, 5, 6, 15, 0, 3, 4, 7, 2, 12, 1, 10, 14, 9],
+     [10, 6, 9, 0, 12, 11, 7, 13, 15, 1, 3, 14, 5, 2, 8, 4],
+     [3, 15, 0, 6, 10, 1, 13, 8, 9, 4, 5, 11, 12, 7, 2, 14]],
+    [[2, 12, 4, 1, 7, 10, 11, 6, 8, 5, 3, 15, 13, 0, 14, 9],
+     [14, 11, 2, 12, 4, 7, 13, 1, 5, 0, 15, 10, 3, 9, 8, 6],
+     [4, 2, 1, 11, 10, 13, 7, 8, 15, 9, 12, 5, 6, 3, 0, 14],
+     [11, 8, 12, 7, 1, 14, 2, 13, 6, 15, 0, 9, 10, 4, 5, 3]],
+    [[12, 1, 10, 15, 9, 2, 6, 8, 0, 13, 3, 4, 14, 7, 5, 11],
+     [10, 15, 4, 2, 7, 12, 9, 5, 6, 1, 13, 14, 0, 11, 3, 8],
+     [9, 14, 15, 5, 2, 8, 12, 3, 7, 0, 4, 10, 1, 13, 11, 6],
+     [4, 3, 2, 12, 9, 5, 15, 10, 11, 14, 1, 7, 6, 0, 8, 13]],
+    [[4, 11, 2, 14, 15, 0, 8, 13, 3, 12, 9, 7, 5, 10, 6, 1],
+     [13, 0, 11, 7, 4, 9, 1, 10, 14, 3, 5, 12, 2, 15, 8, 6],
+     [1, 4, 11, 13, 12, 3, 7, 14, 10, 15, 6, 8, 0, 5, 9, 2],
+     [6, 11, 13, 8, 1, 4, 10, 7, 9, 5, 0, 15, 14, 2, 3, 12]],
+    [[13, 2, 8, 4, 6, 15, 11, 1, 10, 9, 3, 14, 5, 0, 12, 7],
+     [1, 15, 13, 8, 10, 3, 7, 4, 12, 5, 6, 11, 0, 14, 9, 2],
+     [7, 11, 4, 1, 9, 12, 14, 2, 0, 6, 10, 13, 15, 3, 5, 8],
+     [2, 1, 14, 7, 4, 10, 8, 13, 15, 12, 9, 0, 3, 5, 6, 11]],
+]
+
+# Перестановка P из DES (номера битов с единицы)
+DES_P = [16, 7, 20, 21, 29, 12, 28, 17, 1, 15, 23, 26, 5, 18, 31, 10,
+         2, 8, 24, 14, 32, 27, 3, 9, 19, 13, 30, 6, 22, 11, 4, 25]
+
+# Начальная перестановка IP из DES (номера битов с единицы)
+DES_IP = [58, 50, 42, 34, 26, 18, 10, 2, 60, 52, 44, 36, 28, 20, 12, 4,
+          62, 54, 46, 38, 30, 22, 14, 6, 64, 56, 48, 40, 32, 24, 16, 8,
+          57, 49, 41, 33, 25, 17, 9, 1, 59, 51, 43, 35, 27, 19, 11, 3,
+          61, 53, 45, 37, 29, 21, 13, 5, 63, 55, 47, 39, 31, 23, 15, 7]
+
+# Конечная перестановка FP = IP^-1 из DES
+DES_FP = [40, 8, 48, 16, 56, 24, 64, 32, 39, 7, 47, 15, 55, 23, 63, 31,
+          38, 6, 46, 14, 54, 22, 62, 30, 37, 5, 45, 13, 53, 21, 61, 29,
+          36, 4, 44, 12, 52, 20, 60, 28, 35, 3, 43, 11, 51, 19, 59, 27,
+          34, 2, 42, 10, 50, 18, 58, 26, 33, 1, 41, 9, 49, 17, 57, 25]
+
+# Выбор PC-1 (56 битов ключа без битов четности) и PC-2 (48 битов) из DES
+DES_PC1 = [57, 49, 41, 33, 25, 17, 9, 1, 58, 50, 42, 34, 26, 18,
+           10, 2, 59, 51, 43, 35, 27, 19, 11, 3, 60, 52, 44, 36,
+           63, 55, 47, 39, 31, 23, 15, 7, 62, 54, 46, 38, 30, 22,
+           14, 6, 61, 53, 45, 37, 29, 21, 13, 5, 28, 20, 12, 4]
+
+DES_PC2 = [14, 17, 11, 24, 1, 5, 3, 28, 15, 6, 21, 10,
+           23, 19, 12, 4, 26, 8, 16, 7, 27, 20, 13, 2,
+           41, 52, 31, 37, 47, 55, 30, 40, 51, 45, 33, 48,
+           44, 49, 39, 56, 34, 53, 46, 42, 50, 36, 29, 32]
+
+# Сдвиги половин C и D ключа DES по раундам
+DES_SHIFTS = [1, 1, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 1]
+
+# Таблицы перестановок битов: название -> таблица
+PERMUTATION_TABLES = {
+    "DES IP": DES_IP,
+    "DES FP": DES_FP,
+    "DES P": DES_P,
+}
+
+def des_sbox_flat(box):
+    """
+    Переводит S-блок DES из 4 строк по 16 значений в таблицу из 64 значений,
+    индексируемую 6-битным входом b0..b5 (строка b0b5, столбец b1..b4).
+    """
+    return [box[((v >> 4) & 2) | (v & 1)][(v >> 1) & 0xF] for v in range(64)]
+
+DES_SBOXES_FLAT = [des_sbox_flat(box) for box in DES_SBOXES]
+
+# Наборы S-блоков: название -> список S-блоков (по 16, 64 или 256 значений)
+SBOX_SETS = {
+    "ГОСТ Р 34.12-2015 (param-Z)": GOST_SBOX_Z,
+    "ГОСТ 28147-89 тестовый (TestParamSet)": GOST_SBOX_TEST,
+    "КриптоПро A": GOST_SBOX_CRYPTOPRO_A,
+    "КриптоПро B": GOST_SBOX_CRYPTOPRO_B,
+    "КриптоПро C": GOST_SBOX_CRYPTOPRO_C,
+    "КриптоПро D": GOST_SBOX_CRYPTOPRO_D,
+    "DES S1-S8": DES_SBOXES_FLAT,
+    "PRESENT": [PRESENT_SBOX],
+}
+
+def sbox6_layer(bits, sboxes):
+    """
+    Слой S-блоков 6 -> 4 бита в стиле DES: группа j (со старшей)
+    идет через S-блок j.
+    
+    Args:
+        bits: Список битов, длина кратна 6
+        sboxes: S-блоки по 64 значения (см. des_sbox_flat)
+    
+    Returns:
+        Список выходных битов
+    """
+    out = []
+    for j in range(len(bits) // 6):
+        value = 0
+        for bit in bits[6*j:6*j + 6]:
+            value = (value << 1) | bit
+        value = sboxes[j % len(sboxes)][value]
+        out += [(value >> (3 - i)) & 1 for i in range(4)]
+    return out
+
+def substitute(vect, sboxes):
+    """
+    Слой подстановки: заменяет части вектора по набору S-блоков.
+    
+    Размер S-блока определяет ширину подстановки:
+    - 16 значений: 4-битные тетрады, тетрада i (с младшей) идет через
+      S-блок i (как узлы замены ГОСТ);
+    - 256 значений: байты, байт i (с младшего) идет через S-блок i;
+    - 64 значения: S-блоки DES 6 -> 4 бита, вход предварительно
+      расширяется функцией des_expand.
+    S-блоки используются циклически.
+    
+    Args:
+        vect: Исходный вектор байтов
+        sboxes: Список S-блоков одного размера
+    
+    Returns:
+        Вектор той же длины после подстановки
+    """
+    size = len(sboxes[0])
+    if size == 64:
+        return bits_to_vec(sbox6_layer(des_expand(vec_to_bits(vect)), sboxes))
+    
+    width = 4 if size == 16 else 8
+    value = vec_to_int(vect)
+    result = 0
+    for i in range(8 * len(vect) // width):
+        part = (value >> (width * i)) & (size - 1)
+        result |= sboxes[i % len(sboxes)][part] << (width * i)
+    return int_to_vec(result, len(vect))
+
+def parse_sboxes(text):
+    """
+    Разбирает набор S-блоков, заданный пользователем.
+    
+    Каждая непустая строка - один S-блок из 16 (4 бита) или 256 (8 битов)
+    значений, разделенных пробелами или запятыми. Значения записываются
+    в десятичном виде или в HEX с префиксом 0x. Строки, начинающиеся с #,
+    пропускаются.
+    
+    Args:
+        text: Текст с S-блоками
+    
+    Returns:
+        Список S-блоков
+    """
+    sboxes = []
+    for line in text.splitlines():
+        line = line.strip()
+        if not line or line.startswith('#'):
+            continue
+        try:
+            box = [int(x, 0) for x in line.replace(',', ' ').split()]
+        except ValueError:
+            raise ValueError(f"Некорректное значение в S-блоке: {line}")
+        if len(box) not in (16, 256):
+            raise ValueError(f"S-блок должен содержать 16 или 256 значений, а не {len(box)}")
+        if any(not 0 <= x < len(box) for x in box):
+            raise ValueError(f"Значения S-блока из {len(box)} элементов должны лежать в диапазоне 0..{len(box) - 1}")
+        sboxes.append(box)
+    
+    if not sboxes:
+        raise ValueError("Не задано ни одного S-блока")
+    if len({len(box) for box in sboxes}) != 1:
+        raise ValueError("Все S-блоки набора должны иметь одинаковый размер")
+    return sboxes
+
+def load_sboxes(path):
+    """Загружает набор S-блоков из текстового файла (формат см. parse_sboxes)"""
+    with open(path, encoding='utf-8') as file:
+        return parse_sboxes(file.read())
+
+def check_permutation(table, size=None):
+    """
+    Проверяет, что таблица задает перестановку битов.
+    
+    Args:
+        table: Номера битов входа (с единицы) для каждого бита выхода
+        size: Ожидаемое число битов или None
+    """
+    if size is not None and len(table) != size:
+        raise ValueError(f"Таблица перестановки из {len(table)} элементов "
+                         f"не подходит для блока из {size} битов")
+    if sorted(table) != list(range(1, len(table) + 1)):
+        raise ValueError(f"Таблица не является перестановкой: каждый номер бита "
+                         f"от 1 до {len(table)} должен встречаться ровно один раз")
+
+def inverse_permutation(table):
+    """Возвращает таблицу обратной перестановки"""
+    result = [0] * len(table)
+    for i, source in enumerate(table):
+        result[source - 1] = i + 1
+    return result
+
+def permute_bits(vect, table):
+    """
+    Переставляет биты вектора по таблице (как P-блоки и IP/FP в DES).
+    
+    Args:
+        vect: Исходный вектор байтов
+        table: Номера битов входа (с единицы, старший бит - первый)
+            для каждого бита выхода
+    
+    Returns:
+        Вектор той же длины с переставленными битами
+    """
+    check_permutation(table, 8 * len(vect))
+    bits = vec_to_bits(vect)
+    return bits_to_vec([bits[i - 1] for i in table])
+
+def parse_permutation(text):
+    """
+    Разбирает таблицу перестановки: номера битов с единицы через пробелы
+    или запятые.
+    
+    Returns:
+        Таблица перестановки или None для пустого текста
+    """
+    try:
+        table = [int(x) for x in text.replace(',', ' ').split()]
+    except ValueError:
+        raise ValueError("Номера битов в таблице перестановки должны быть целыми числами")
+    if not table:
+        return None
+    check_permutation(table)
+    return table
+
+def f_sbox(right, key, sboxes=None):
+    """
+    Функция F с подстановкой: XOR с ключом, затем слой S-блоков
+    (по умолчанию S-блок PRESENT для каждой тетрады).
+    """
+    return substitute(vec_xor(right, key), sboxes or [PRESENT_SBOX])
+
+def f_arx(right, key):
+    """
+    ARX-функция F: сложение с ключом по модулю 2^n, циклический сдвиг
+    на 7 битов и XOR с исходной половиной блока.
+    """
+    bits = 8 * len(right)
+    x = (vec_to_int(right) + vec_to_int(key)) % (1 << bits)
+    return int_to_vec(rotl(x, 7, bits) ^ vec_to_int(right), len(right))
+
+def des_expand(bits):
+    """
+    Расширение E в стиле DES: каждая тетрада дополняется крайними битами
+    соседних тетрад (циклически) до 6 битов.
+    """
+    result = []
+    for i in range(0, len(bits), 4):
+        result += [bits[i - 1]] + bits[i:i + 4] + [bits[(i + 4) % len(bits)]]
+    return result
+
+def f_des(right, key):
+    """
+    Функция F в стиле DES: расширение E половины блока и ключа, XOR,
+    S-блоки DES (6 битов -> 4 бита) и перестановка битов.
+    
+    Для 32-битной половины используется перестановка P из DES,
+    для других размеров - перестановка с шагом 7.
+    """
+    x = vec_xor(des_expand(vec_to_bits(right)), des_expand(vec_to_bits(key)))
+    out = sbox6_layer(x, DES_SBOXES_FLAT)
+    
+    if len(out) == 32:
+        out = [out[i - 1] for i in DES_P]
+    else:
+        stride = 7 if len(out) % 7 != 0 else 5
+        out = [out[(i * stride) % len(out)] for i in range(len(out))]
+    return bits_to_vec(out)
+
+def gost_word(word, key, bits, sboxes=GOST_SBOX_Z):
+    """
+    Преобразование g[k] ГОСТ над одним словом: сложение по модулю 2^bits,
+    подстановка по узлам замены и циклический сдвиг влево на 11 битов.
+    """
+    x = (word + key) % (1 << bits)
+    # Тетрада i (с младшей) идет через узел π_i
+    result = vec_to_int(substitute(int_to_vec(x, bits // 8), sboxes))
+    return rotl(result, 11, bits)
+
+def f_gost(right, key, sboxes=GOST_SBOX_Z):
+    """
+    Функция F в стиле ГОСТ 28147-89: для каждого 32-битного слова
+    половины блока выполняется преобразование g[k] с узлами замены sboxes.
+    """
+    result = []
+    for i in range(0, len(right), 4):
+        word = right[i:i + 4]
+        bits = 8 * len(word)
+        x = gost_word(vec_to_int(word), vec_to_int(key[i:i + 4]), bits, sboxes)
+        result += int_to_vec(x, len(word))
+    return result
+
+def f_rotate(right, key, shift=1):
+    """
+    Классическая функция F с циклическим сдвигом всей половины блока
+    на shift битов вместо побайтового bit_left.
+    """
+    return vec_rotl(vec_invert(vec_xor(right, key)), shift)
+
+def f_hmac(right, key):
+    """
+    Функция F - ключевая псевдослучайная функция HMAC-SHA256 (конструкция
+    Люби-Ракоффа): ключ раунда служит ключом HMAC, выход обрезается
+    до ширины половины блока (для широких половин HMAC вычисляется
+    со счетчиком).
+    """
+    output = b''
+    counter = 0
+    while len(output) < len(right):
+        message = bytes(right) + (bytes([counter]) if counter else b'')
+        output += hmac.new(bytes(key), message, hashlib.sha256).digest()
+        counter += 1
+    return list(output[:len(right)])
+
+def f_simon(right, key):
+    """
+    Функция F шифра Simon: (S^1 x & S^8 x) ⊕ S^2 x ⊕ k, где S^j - циклический
+    сдвиг всей половины влево на j битов. Единственная нелинейность - AND.
+    """
+    x = right
+    return vec_xor(vec_xor(vec_and(vec_rotl(x, 1), vec_rotl(x, 8)), vec_rotl(x, 2)), key)
+
+def f_pbox(right, key, round_func=f, table=None):
+    """
+    Функция F с P-блоком: выход функции round_func проходит
+    через перестановку битов table (см. permute_bits).
+    """
+    return permute_bits(round_func(right, key), table)
+
+def pbox_round_funcs(round_func, table):
+    """Добавляет P-блок к функции F или к каждой функции списка по раундам"""
+    if isinstance(round_func, list):
+        return [pbox_round_funcs(fn, table) for fn in round_func]
+    return functools.partial(f_pbox, round_func=round_func, table=table)
+
+def pbox_table(round_func):
+    """Возвращает таблицу P-блока функции F или None, если P-блока нет"""
+    if isinstance(round_func, functools.partial) and round_func.func is f_pbox:
+        return round_func.keywords['table']
+    return None
+
+def rotation_round_funcs(shifts):
+    """
+    Строит список функций f_rotate со своим сдвигом для каждого раунда.
+    
+    Args:
+        shifts: Сдвиги по раундам в битах (отрицательные - вправо),
+            используются циклически
+    
+    Returns:
+        Список функций F для параметра round_func
+    """
+    return [functools.partial(f_rotate, shift=shift) for shift in shifts]
+
+def rotation_shift(round_func):
+    """Возвращает сдвиг функции f_rotate или None для других функций F"""
+    if pbox_table(round_func) is not None:
+        round_func = round_func.keywords['round_func']
+    if round_func is f_rotate:
+        return 1
+    if isinstance(round_func, functools.partial) and round_func.func is f_rotate:
+        return round_func.keywords.get('shift', 1)
+    return None
+
+def round_func_at(round_func, index):
+    """
+    Выбирает функцию F для раунда шифрования с номером index (с нуля).
+    
+    Args:
+        round_func: Функция F или список функций по раундам (циклически)
+        index: Номер раунда шифрования
+    """
+    if isinstance(round_func, (list, tuple)):
+        return round_func[index % len(round_func)]
+    return round_func
+
+# Реестр функций раунда: название -> функция F(right, key)
+ROUND_FUNCTIONS = {
+    "Классическая": f,
+    "Циклический сдвиг": f_rotate,
+    "S-блок": f_sbox,
+    "ARX": f_arx,
+    "DES": f_des,
+    "ГОСТ": f_gost,
+    "HMAC-SHA256 (ПСФ)": f_hmac,
+    "Simon (AND и сдвиги)": f_simon,
+}
+
+def schedule_permute(key, rounds, width):
+    """Ключ раунда i - перестановка байтов ключа permute_word(key, i)"""
+    key = fit_key(key, width)
+    return [permute_word(key.copy(), i) for i in range(rounds)]
+
+def schedule_rotate(key, rounds, width):
+    """
+    Ключ раунда i - старшие width байтов мастер-ключа, циклически
+    сдвинутого влево на 5*i битов.
+    """
+    master = fit_key(key, max(len(key), width))
+    bits = 8 * len(master)
+    value = vec_to_int(master)
+    return [int_to_vec(rotl(value, 5 * i, bits), len(master))[:width] 
+            for i in range(rounds)]
+
+def schedule_gost(key, rounds, width):
+    """
+    Порядок ГОСТ 28147-89: ключ приводится к 8 словам K1..K8 ширины width,
+    которые используются по порядку, а в последних 8 раундах - в обратном
+    порядке (для 32 раундов: K1..K8 трижды, затем K8..K1).
+    """
+    key = fit_key(key, 8 * width)
+    words = [key[i * width:(i + 1) * width] for i in range(8)]
+    res = []
+    for i in range(rounds):
+        if i < rounds - 8:
+            res.append(words[i % 8].copy())
+        else:
+            res.append(words[7 - (i - rounds + 8)].copy())
+    return res
+
+# Отводы РСЛОС (номера разрядов), дающие максимальный период
+LFSR_TAPS = {
+    16: [16, 15, 13, 4],
+    32: [32, 22, 2, 1],
+    64: [64, 63, 61, 60],
+    128: [128, 126, 101, 99],
+}
+
+def schedule_lfsr(key, rounds, width):
+    """
+    Расширение ключа регистром сдвига с линейной обратной связью (РСЛОС):
+    регистр заполняется ключом, для каждого раунда выдвигается
+    8*width новых битов.
+    """
+    bits = 8 * width
+    taps = LFSR_TAPS.get(bits, [bits, bits - 1])
+    state = vec_to_int(fit_key(key, width)) or 1  # Нулевое состояние не меняется
+    res = []
+    for _ in range(rounds):
+        for _ in range(bits):
+            feedback = 0
+            for tap in taps:
+                feedback ^= (state >> (tap - 1)) & 1
+            state = ((state << 1) | feedback) & ((1 << bits) - 1)
+        res.append(int_to_vec(state, width))
+    return res
+
+def schedule_constants(key, rounds, width):
+    """
+    Ключ раунда i - мастер-ключ, сдвинутый на 3*i битов, сложенный по XOR
+    с константой раунда C_i = (i+1) * floor(2^n / φ) mod 2^n.
+    """
+    bits = 8 * width
+    value = vec_to_int(fit_key(key, width))
+    golden = (math.isqrt(5 << (2 * bits)) - (1 << bits)) >> 1  # 2^n / φ
+    return [int_to_vec(rotl(value, 3 * i, bits) ^ ((i + 1) * golden % (1 << bits)), width) 
+            for i in range(rounds)]
+
+# Реестр алгоритмов расписания ключей: название -> функция (key, rounds, width)
+KEY_SCHEDULES = {
+    "Перестановка байтов": schedule_permute,
+    "Циклический сдвиг": schedule_rotate,
+    "ГОСТ 28147-89": schedule_gost,
+    "РСЛОС": schedule_lfsr,
+    "С константами раундов": schedule_constants,
+}
+
+def tweak_keys(keys, tweak):
+    """
+    Подмешивает твик в ключи раундов, как в FF3: твик приводится к двойной
+    ширине ключа и делится на половины T_L и T_R, четные раунды (с нуля)
+    складываются по XOR с T_L, нечетные - с T_R.
+    
+    Args:
+        keys: Ключи раундов в порядке шифрования
+        tweak: Твик (список байтов)
+    
+    Returns:
+        Ключи раундов, зависящие от твика
+    """
+    width = len(keys[0]) if keys else 0
+    tweak = fit_key(tweak, 2 * width)
+    halves = [tweak[:width], tweak[width:]]
+    return [vec_xor(k, halves[i % 2]) for i, k in enumerate(keys)]
+
+def keys_gen(key, decrypt, rounds, width, schedule=schedule_permute, tweak=None):
     """
     Генерирует последовательность ключей для каждого раунда шифрования/дешифрования.
     
@@ -86,112 +734,2225 @@ def keys_gen(key, decrypt, rounds):
         key: Базовый ключ шифрования
         decrypt: Флаг режима (True для дешифрования, False для шифрования)
         rounds: Количество раундов шифрования
+        width: Ширина ключа раунда в байтах (равна половине блока)
+        schedule: Алгоритм расписания ключей (см. KEY_SCHEDULES)
+        tweak: Твик, подмешиваемый в ключи раундов (см. tweak_keys), или None
     
     Returns:
         Список ключей для всех раундов
     """
-    res = []
-    # Генерируем уникальный ключ для каждого раунда
-    for i in range(rounds):
-        res.append(permute_word(key.copy(), i))
+    # Генерируем ключ для каждого раунда по выбранному расписанию
+    res = schedule(key, rounds, width)
+    if tweak:
+        res = tweak_keys(res, tweak)
     
     # Для дешифрования используем ключи в обратном порядке
     if decrypt:
         res.reverse()
     return res
 
-def crypt_round(block, round_key):
+def crypt_round(block, round_key, round_func=f, split=None, key_width=None):
     """
     Выполняет один раунд шифрования в сети Фейстеля.
     
+    В несбалансированной сети левая (целевая) и правая (исходная) части
+    имеют разный размер: функция F вычисляется от правой части, а ее выход
+    приводится к размеру левой части функцией fit_key.
+    
     Args:
         block: Блок данных для шифрования
-        round_key: Ключ текущего раунда
+        round_key: Ключ текущего раунда (ширины правой части)
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        split: Размер левой части в байтах (по умолчанию половина блока)
+        key_width: Ширина ключа раунда в байтах, если она отличается
+            от правой части (48-битные ключи DES)
     
     Returns:
         Преобразованный блок после одного раунда
     """
+    if split is None:
+        split = len(block) // 2
+    
     # Разделяем блок на левую и правую части
-    left = block[:len(block)//2]
-    right = block[len(block)//2:]
+    left = block[:split]
+    right = block[split:]
+    
+    # Ключ другой ширины изменил бы длину блока и сделал бы раунд необратимым
+    if key_width is not None:
+        if not left or len(round_key) != key_width:
+            raise ValueError(f"Ключ раунда ({len(round_key)} байт) должен иметь длину {key_width} байт")
+    elif not left or len(round_key) != len(right):
+        raise ValueError(f"Ключ раунда ({len(round_key)} байт) должен совпадать по длине "
+                         f"с правой частью блока ({len(right)} байт)")
     
     # Применяем функцию Фейстеля к правой части и выполняем XOR с левой частью
-    new_right = vec_xor(left, f(right.copy(), round_key))
+    new_right = vec_xor(left, fit_key(round_func(right.copy(), round_key), len(left)))
     
     # Новый блок: старая правая часть становится левой, а результат XOR - новой правой
     return right + new_right
 
-def crypt_block(block, key, decrypt, rounds):
+def crypt_round_inverse(block, round_key, round_func=f, split=None):
+    """
+    Обращает раунд crypt_round с тем же ключом.
+    
+    Нужен для несбалансированной сети, где дешифрование нельзя свести
+    к шифрованию с обратным порядком ключей.
+    
+    Args:
+        block: Блок после раунда crypt_round
+        round_key: Ключ этого раунда
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        split: Размер левой части исходного блока в байтах
+    
+    Returns:
+        Блок до раунда
+    """
+    if split is None:
+        split = len(block) // 2
+    
+    # После раунда блок имеет вид: правая часть + (левая часть XOR F)
+    right = block[:len(block) - split]
+    new_right = block[len(block) - split:]
+    
+    left = vec_xor(new_right, fit_key(round_func(right.copy(), round_key), split))
+    return left + right
+
+def whitening_keys(key, size):
+    """
+    Выводит ключи отбеливания из мастер-ключа.
+    
+    K_pre - мастер-ключ, приведенный к размеру блока; K_post - инверсия
+    K_pre, циклически сдвинутого на половину блока, чтобы ключи
+    на входе и выходе различались.
+    
+    Args:
+        key: Мастер-ключ
+        size: Размер блока в байтах
+    
+    Returns:
+        Пара ключей (K_pre, K_post)
+    """
+    pre = fit_key(key, size)
+    return pre, vec_invert(vec_rotl(pre, 4 * size))
+
+def whiten(block, whitening_key):
+    """Складывает блок с ключом отбеливания по XOR"""
+    if len(whitening_key) != len(block):
+        raise ValueError(f"Ключ отбеливания ({len(whitening_key)} байт) должен совпадать "
+                         f"по длине с блоком ({len(block)} байт)")
+    return vec_xor(block, whitening_key)
+
+# Способы получения ключей отбеливания
+WHITENING_MODES = ["Нет", "Из мастер-ключа", "Заданные ключи"]
+
+def split_point(size, percent):
+    """
+    Вычисляет размер левой части блока по ее доле в процентах.
+    
+    Args:
+        size: Размер блока в байтах
+        percent: Доля левой (целевой) части блока в процентах
+    
+    Returns:
+        Размер левой части в байтах (от 1 до size-1)
+    """
+    return min(max(round(size * percent / 100), 1), size - 1)
+
+def crypt_block(block, key, decrypt, rounds, round_func=f, schedule=schedule_permute, 
+                split=None, trace=None, ip=None, fp=None, whitening=None, tweak=None, 
+                key_width=None):
     """
     Шифрует или дешифрует блок данных с использованием сети Фейстеля.
     
+    Необязательные начальная (IP) и конечная (FP) перестановки битов
+    выполняются до первого раунда и после финальной перестановки половин,
+    как в DES; при дешифровании они обращаются и меняются местами.
+    Отбеливание (как в DES-X) добавляет ключи по XOR к блоку на входе
+    и выходе шифра, снаружи перестановок IP/FP.
+    
     Args:
         block: Блок данных для шифрования/дешифрования
         key: Ключ шифрования
         decrypt: Флаг режима (True для дешифрования, False для шифрования)
         rounds: Количество раундов шифрования
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS) или список
+            функций по раундам
+        schedule: Алгоритм расписания ключей (см. KEY_SCHEDULES)
+        split: Размер левой части в байтах; отличный от половины блока
+            размер задает несбалансированную сеть
+        trace: Список, в который записываются промежуточные состояния
+            (название, блок, ключ раунда или таблица перестановки) для визуализации
+        ip: Таблица начальной перестановки битов или None
+        fp: Таблица конечной перестановки битов; по умолчанию обратная к ip
+        whitening: Пара ключей отбеливания (K_pre, K_post) размера блока
+            или None (см. whitening_keys)
+        tweak: Твик: с разными твиками одинаковые блоки шифруются
+            по-разному при одном ключе (см. tweak_keys)
+        key_width: Ширина ключей раундов в байтах (по умолчанию ширина
+            правой части)
     
     Returns:
         Зашифрованный или дешифрованный блок данных
     """
-    # Генерируем ключи для всех раундов
-    keys = keys_gen(key, decrypt, rounds)
+    pre, post = whitening if whitening is not None else (None, None)
+    if decrypt:
+        pre, post = post, pre
+    
+    if ip is not None and fp is None:
+        fp = inverse_permutation(ip)
+    if fp is not None and ip is None:
+        ip = inverse_permutation(fp)
+    if ip is not None and decrypt:
+        ip, fp = inverse_permutation(fp), inverse_permutation(ip)
+    
+    if split is None:
+        if len(block) == 0 or len(block) % 2 != 0:
+            raise ValueError(f"Блок ({len(block)} байт) нельзя разделить на две равные части")
+        split = len(block) // 2
+    elif not 0 < split < len(block):
+        raise ValueError(f"Точка разделения {split} должна лежать внутри блока ({len(block)} байт)")
+    
+    if trace is not None:
+        trace.append(("Начальный блок", block.copy(), None))
+    
+    if pre is not None:
+        block = whiten(block, pre)
+        if trace is not None:
+            trace.append(("Отбеливание на входе", block.copy(), pre))
+    
+    if ip is not None:
+        block = permute_bits(block, ip)
+        if trace is not None:
+            trace.append(("Начальная перестановка", block.copy(), ip))
+    
+    # Генерируем ключи для всех раундов (ширины правой части)
+    keys = keys_gen(key, decrypt, rounds, key_width or len(block) - split, schedule, tweak)
+    
+    if decrypt and 2 * split != len(block):
+        # Несбалансированная сеть: отменяем финальную перестановку
+        # и обращаем раунды в обратном порядке
+        block = block[split:] + block[:split]
+        for i, round_key in enumerate(keys):
+            block = crypt_round_inverse(block, round_key, 
+                                        round_func_at(round_func, rounds - 1 - i), split)
+            if trace is not None:
+                trace.append((f"Раунд {i+1}", block.copy(), round_key))
+        return finish_block(block, fp, post, trace)
     
     # Выполняем указанное количество раундов шифрования
-    for round_key in keys:
-        block = crypt_round(block, round_key)
+    # (при дешифровании раунды идут в обратном порядке)
+    for i, round_key in enumerate(keys):
+        index = rounds - 1 - i if decrypt else i
+        block = crypt_round(block, round_key, round_func_at(round_func, index), split, key_width)
+        if trace is not None:
+            trace.append((f"Раунд {i+1}", block.copy(), round_key))
     
-    # Выполняем финальную перестановку (меняем местами левую и правую части)
-    left = block[:len(block)//2]
-    right = block[len(block)//2:]
-    return right + left
+    # Выполняем финальную перестановку (возвращаем левую часть на место)
+    left = block[:len(block) - split]
+    right = block[len(block) - split:]
+    block = right + left
+    return finish_block(block, fp, post, trace)
 
-def pad_block(block):
-    """Дополняет блок до четной длины, если необходимо"""
-    if len(block) % 2 != 0:
-        return block + [0]  # Добавляем нулевой байт
+def finish_block(block, fp, post, trace=None):
+    """
+    Выполняет конечную перестановку битов FP и отбеливание на выходе
+    (если они заданы) и завершает трассировку.
+    """
+    if fp is not None:
+        block = permute_bits(block, fp)
+        if trace is not None:
+            trace.append(("Конечная перестановка", block.copy(), fp))
+    if post is not None:
+        block = whiten(block, post)
+        if trace is not None:
+            trace.append(("Отбеливание на выходе", block.copy(), post))
+    if trace is not None:
+        trace.append(("Финальный результат", block.copy(), None))
     return block
 
-class FeistelBlockItem:
-    """Класс для визуализации блока данных в сети Фейстеля"""
+def gfn_type1(k):
+    """Type-1: F от ветви X0 добавляется к ветви X1"""
+    return [((0,), 1)]
+
+def gfn_type2(k):
+    """Type-2: в каждой паре ветвей (X2i, X2i+1) F от первой добавляется ко второй"""
+    if k % 2 != 0:
+        raise ValueError(f"Сети Type-2 нужно четное число ветвей, а не {k}")
+    return [((j,), j + 1) for j in range(0, k, 2)]
+
+def gfn_type3(k):
+    """Type-3: F от каждой ветви Xi добавляется к следующей ветви Xi+1"""
+    return [((j,), j + 1) for j in range(k - 1)]
+
+def gfn_sm4(k):
+    """SM4: F от суммы по XOR ветвей X1..Xk-1 добавляется к ветви X0"""
+    return [(tuple(range(1, k)), 0)]
+
+# Обобщенные сети Фейстеля: название -> функция, возвращающая для k ветвей
+# список пар (ветви-источники, ветвь-приемник) одного раунда
+GFN_TYPES = {
+    "Type-1": gfn_type1,
+    "Type-2": gfn_type2,
+    "Type-3": gfn_type3,
+    "SM4": gfn_sm4,
+}
+
+def gfn_round(branches, round_keys, round_func, gfn_type):
+    """
+    Выполняет один раунд обобщенной сети Фейстеля.
     
-    def __init__(self, scene, x, y, width, height, left_data, right_data, title=""):
-        self.scene = scene
-        self.x = x
-        self.y = y
-        self.width = width
-        self.height = height
-        self.left_data = left_data
-        self.right_data = right_data
-        self.title = title
-        
-        self.draw()
-        
-    def draw(self):
-        # Рисуем общую рамку
-        self.scene.addRect(self.x, self.y, self.width, self.height, 
-                           QPen(Qt.GlobalColor.black), QBrush(Qt.GlobalColor.white))
-        
-        # Рисуем разделение на левую и правую части
-        self.scene.addLine(self.x + self.width/2, self.y, 
-                           self.x + self.width/2, self.y + self.height, 
-                           QPen(Qt.GlobalColor.black))
-        
-        # Добавляем заголовок
-        if self.title:
-            title_item = self.scene.addText(self.title, QFont("Arial", 10))
-            title_item.setPos(self.x + self.width/2 - title_item.boundingRect().width()/2, 
-                             self.y - 20)
-        
-        # Отображаем данные левой и правой частей
-        left_text = self.format_data(self.left_data)
-        right_text = self.format_data(self.right_data)
-        
-        left_item = self.scene.addText(left_text, QFont("Courier", 8))
-        right_item = self.scene.addText(right_text, QFont("Courier", 8))
-        
-        left_item.setPos(self.x + 5, self.y + 5)
-        right_item.setPos(self.x + self.width/2 + 5, self.y + 5)
+    К ветвям-приемникам добавляется по XOR функция F от ветвей-источников
+    (каждая пара использует свой ключ), затем ветви циклически сдвигаются
+    влево на одну позицию. При двух ветвях это обычная сеть Фейстеля.
+    
+    Args:
+        branches: Список ветвей (векторов байтов одинаковой длины)
+        round_keys: Ключи раунда, по одному на каждую пару источник-приемник
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        gfn_type: Название структуры из GFN_TYPES
+    
+    Returns:
+        Список ветвей после раунда
+    """
+    result = [x.copy() for x in branches]
+    for (sources, dest), round_key in zip(GFN_TYPES[gfn_type](len(branches)), round_keys):
+        source = [0] * len(round_key)
+        for i in sources:
+            source = vec_xor(source, branches[i])
+        result[dest] = vec_xor(result[dest], round_func(source, round_key))
+    return result[1:] + result[:1]
+
+def gfn_round_inverse(branches, round_keys, round_func, gfn_type):
+    """
+    Обращает раунд gfn_round с теми же ключами.
+    
+    Args:
+        branches: Список ветвей после раунда
+        round_keys: Ключи этого раунда
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        gfn_type: Название структуры из GFN_TYPES
+    
+    Returns:
+        Список ветвей до раунда
+    """
+    shifted = branches[-1:] + branches[:-1]
+    result = [x.copy() for x in shifted]
+    # Пары обрабатываются по порядку: в Type-3 источник каждой пары
+    # восстанавливается на предыдущем шаге
+    for (sources, dest), round_key in zip(GFN_TYPES[gfn_type](len(branches)), round_keys):
+        source = [0] * len(round_key)
+        for i in sources:
+            source = vec_xor(source, result[i])
+        result[dest] = vec_xor(shifted[dest], round_func(source, round_key))
+    return result
+
+def gfn_wiring(gfn_type, k, inverse=False):
+    """
+    Описывает соединения ветвей в одном раунде для визуализации.
+    
+    Args:
+        gfn_type: Название структуры из GFN_TYPES
+        k: Число ветвей
+        inverse: True для обращенного раунда
+    
+    Returns:
+        Список (входная ветвь, выходная ветвь, добавляется ли F)
+    """
+    targets = {dest for _, dest in GFN_TYPES[gfn_type](k)}
+    if inverse:
+        return [(i, (i + 1) % k, (i + 1) % k in targets) for i in range(k)]
+    return [(i, (i - 1) % k, i in targets) for i in range(k)]
+
+def gfn_diffusion_rounds(gfn_type, k, limit=100):
+    """
+    Вычисляет число раундов до полной диффузии: после него каждая
+    выходная ветвь зависит от всех входных ветвей.
+    
+    Args:
+        gfn_type: Название структуры из GFN_TYPES
+        k: Число ветвей
+        limit: Максимальное число проверяемых раундов
+    
+    Returns:
+        Число раундов или None, если диффузия не достигается за limit раундов
+    """
+    pairs = GFN_TYPES[gfn_type](k)
+    deps = [{i} for i in range(k)]  # От каких входных ветвей зависит каждая ветвь
+    for r in range(1, limit + 1):
+        result = [d.copy() for d in deps]
+        for sources, dest in pairs:
+            for i in sources:
+                result[dest] |= deps[i]
+        deps = result[1:] + result[:1]
+        if all(len(d) == k for d in deps):
+            return r
+    return None
+
+def crypt_block_gfn(block, key, decrypt, rounds, branches, gfn_type="Type-2", 
+                    round_func=f, schedule=schedule_permute, trace=None, tweak=None):
+    """
+    Шифрует или дешифрует блок обобщенной сетью Фейстеля с несколькими ветвями.
+    
+    Финальная перестановка не выполняется: дешифрование обращает раунды
+    в обратном порядке.
+    
+    Args:
+        block: Блок данных для шифрования/дешифрования
+        key: Ключ шифрования
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        rounds: Количество раундов шифрования
+        branches: Число ветвей
+        gfn_type: Название структуры из GFN_TYPES
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        schedule: Алгоритм расписания ключей (см. KEY_SCHEDULES)
+        trace: Список для записи промежуточных состояний (см. crypt_block)
+        tweak: Твик, подмешиваемый в ключи раундов (см. tweak_keys)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок данных
+    """
+    if branches < 2 or len(block) % branches != 0:
+        raise ValueError(f"Блок ({len(block)} байт) нельзя разделить на ветви равной длины "
+                         f"(число ветвей: {branches})")
+    width = len(block) // branches
+    
+    # Каждому раунду нужно столько ключей, сколько в нем пар источник-приемник
+    per_round = len(GFN_TYPES[gfn_type](branches))
+    keys = keys_gen(key, False, rounds * per_round, width, schedule, tweak)
+    round_keys = [keys[i * per_round:(i + 1) * per_round] for i in range(rounds)]
+    
+    if trace is not None:
+        trace.append(("Начальный блок", block.copy(), None))
+    
+    state = [block[i * width:(i + 1) * width] for i in range(branches)]
+    order = reversed(range(rounds)) if decrypt else range(rounds)
+    for n, i in enumerate(order):
+        func = round_func_at(round_func, i)
+        if decrypt:
+            state = gfn_round_inverse(state, round_keys[i], func, gfn_type)
+        else:
+            state = gfn_round(state, round_keys[i], func, gfn_type)
+        if trace is not None:
+            trace.append((f"Раунд {n+1}", sum(state, []), sum(round_keys[i], [])))
+    
+    block = sum(state, [])
+    if trace is not None:
+        trace.append(("Финальный результат", block.copy(), None))
+    return block
+
+# Название структуры Лая-Мэсси в списке структур сети
+LAI_MASSEY = "Лай-Мэсси"
+
+def orthomorphism(vect):
+    """
+    Ортоморфизм σ(a, b) = (b, a XOR b) над половинами вектора (как в FOX).
+    Отображения x -> σ(x) и x -> σ(x) XOR x оба обратимы.
+    """
+    bits = 4 * len(vect)
+    value = vec_to_int(vect)
+    a, b = value >> bits, value & ((1 << bits) - 1)
+    return int_to_vec((b << bits) | (a ^ b), len(vect))
+
+def orthomorphism_inverse(vect):
+    """Обратное отображение σ^-1(c, d) = (c XOR d, c)"""
+    bits = 4 * len(vect)
+    value = vec_to_int(vect)
+    c, d = value >> bits, value & ((1 << bits) - 1)
+    return int_to_vec(((c ^ d) << bits) | c, len(vect))
+
+def lai_massey_round(block, round_key, round_func=f, last=False):
+    """
+    Выполняет один раунд схемы Лая-Мэсси.
+    
+    F вычисляется от разности половин D = L XOR R, поэтому одинаково
+    добавляется к обеим половинам: L' = σ(L XOR T), R' = R XOR T, где
+    T = F(D, K). В последнем раунде ортоморфизм σ не применяется.
+    
+    Args:
+        block: Блок данных
+        round_key: Ключ текущего раунда (ширины половины блока)
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        last: True для последнего раунда
+    
+    Returns:
+        Преобразованный блок после одного раунда
+    """
+    left = block[:len(block)//2]
+    right = block[len(block)//2:]
+    t = round_func(vec_xor(left, right), round_key)
+    new_left = vec_xor(left, t)
+    if not last:
+        new_left = orthomorphism(new_left)
+    return new_left + vec_xor(right, t)
+
+def lai_massey_round_inverse(block, round_key, round_func=f, last=False):
+    """
+    Обращает раунд lai_massey_round с тем же ключом.
+    
+    После снятия σ разность половин равна исходной D = L XOR R,
+    поэтому T = F(D, K) вычисляется заново без знания L и R.
+    """
+    left = block[:len(block)//2]
+    right = block[len(block)//2:]
+    if not last:
+        left = orthomorphism_inverse(left)
+    t = round_func(vec_xor(left, right), round_key)
+    return vec_xor(left, t) + vec_xor(right, t)
+
+def crypt_block_lai_massey(block, key, decrypt, rounds, round_func=f, 
+                           schedule=schedule_permute, trace=None, tweak=None):
+    """
+    Шифрует или дешифрует блок по схеме Лая-Мэсси (IDEA, FOX).
+    
+    Args:
+        block: Блок данных для шифрования/дешифрования
+        key: Ключ шифрования
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        rounds: Количество раундов шифрования
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        schedule: Алгоритм расписания ключей (см. KEY_SCHEDULES)
+        trace: Список для записи промежуточных состояний (см. crypt_block)
+        tweak: Твик, подмешиваемый в ключи раундов (см. tweak_keys)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок данных
+    """
+    if len(block) == 0 or len(block) % 2 != 0:
+        raise ValueError(f"Блок ({len(block)} байт) нельзя разделить на две равные части")
+    
+    keys = keys_gen(key, decrypt, rounds, len(block) // 2, schedule, tweak)
+    
+    if trace is not None:
+        trace.append(("Начальный блок", block.copy(), None))
+    
+    for i, round_key in enumerate(keys):
+        # Последний раунд шифрования (первый при дешифровании) идет без σ
+        last = i == (0 if decrypt else len(keys) - 1)
+        func = round_func_at(round_func, rounds - 1 - i if decrypt else i)
+        if decrypt:
+            block = lai_massey_round_inverse(block, round_key, func, last)
+        else:
+            block = lai_massey_round(block, round_key, func, last)
+        if trace is not None:
+            trace.append((f"Раунд {i+1}", block.copy(), round_key))
+    
+    if trace is not None:
+        trace.append(("Финальный результат", block.copy(), None))
+    return block
+
+def gf_double(a):
+    """Умножение байта на x (т.е. на 2) в поле GF(2^8) AES"""
+    return ((a << 1) ^ (0x1B if a & 0x80 else 0)) & 0xFF
+
+def aes_sbox():
+    """
+    Строит S-блок AES: обращение в GF(2^8) и аффинное преобразование.
+    
+    p пробегает степени порождающего элемента 3, q - степени обратного
+    к нему, поэтому q = p^-1 на каждом шаге.
+    """
+    sbox = [0x63] * 256
+    p = q = 1
+    while True:
+        p ^= gf_double(p)
+        q ^= q << 1
+        q ^= q << 2
+        q ^= q << 4
+        q &= 0xFF
+        if q & 0x80:
+            q ^= 0x09
+        sbox[p] = q ^ rotl(q, 1, 8) ^ rotl(q, 2, 8) ^ rotl(q, 3, 8) ^ rotl(q, 4, 8) ^ 0x63
+        if p == 1:
+            return sbox
+
+AES_SBOX = aes_sbox()
+
+def aes_expand_key(key):
+    """
+    Расширение ключа AES-128/192/256.
+    
+    Args:
+        key: Ключ длиной 16, 24 или 32 байта
+    
+    Returns:
+        Список ключей раундов по 16 байтов
+    """
+    if len(key) not in (16, 24, 32):
+        raise ValueError(f"Ключ AES должен иметь длину 16, 24 или 32 байта, а не {len(key)}")
+    nk = len(key) // 4
+    rounds = nk + 6
+    words = [key[4 * i:4 * i + 4] for i in range(nk)]
+    rcon = 1
+    for i in range(nk, 4 * (rounds + 1)):
+        word = words[i - 1]
+        if i % nk == 0:
+            word = [AES_SBOX[b] for b in word[1:] + word[:1]]
+            word[0] ^= rcon
+            rcon = gf_double(rcon)
+        elif nk > 6 and i % nk == 4:
+            word = [AES_SBOX[b] for b in word]
+        words.append(vec_xor(words[i - nk], word))
+    return [sum(words[4 * r:4 * r + 4], []) for r in range(rounds + 1)]
+
+def aes_encrypt(block, round_keys):
+    """
+    Шифрует 16-байтовый блок AES (блочный шифр CIPH для FF1 и FF3-1).
+    
+    Args:
+        block: Блок из 16 байтов
+        round_keys: Ключи раундов из aes_expand_key
+    
+    Returns:
+        Зашифрованный блок
+    """
+    state = vec_xor(block, round_keys[0])
+    for r in range(1, len(round_keys)):
+        # SubBytes и ShiftRows: байт строки i % 4 сдвигается на i % 4 столбцов
+        state = [AES_SBOX[b] for b in state]
+        state = [state[(i + 4 * (i % 4)) % 16] for i in range(16)]
+        # MixColumns (кроме последнего раунда)
+        if r != len(round_keys) - 1:
+            mixed = []
+            for c in range(0, 16, 4):
+                col = state[c:c + 4]
+                total = col[0] ^ col[1] ^ col[2] ^ col[3]
+                mixed += [col[i] ^ total ^ gf_double(col[i] ^ col[(i + 1) % 4]) for i in range(4)]
+            state = mixed
+        state = vec_xor(state, round_keys[r])
+    return state
+
+def num_radix(digits, radix):
+    """NUM_radix(X): число, записанное цифрами digits (старшая первой)"""
+    value = 0
+    for digit in digits:
+        value = value * radix + digit
+    return value
+
+def str_radix(value, radix, length):
+    """STR^m_radix(x): запись числа value ровно length цифрами (старшая первой)"""
+    digits = []
+    for _ in range(length):
+        value, digit = divmod(value, radix)
+        digits.append(digit)
+    return digits[::-1]
+
+# Наименьший размер домена FPE по NIST SP 800-38G Rev.1: radix^minlen >= 10^6
+FPE_MIN_DOMAIN = 10 ** 6
+
+def check_fpe_domain(digits, radix, max_len):
+    """Проверяет основание и длину строки цифр для FF1/FF3-1"""
+    if not 2 <= radix <= 1 << 16:
+        raise ValueError(f"Основание системы счисления должно лежать в диапазоне 2..65536, а не {radix}")
+    if any(not 0 <= d < radix for d in digits):
+        raise ValueError(f"Цифры строки должны лежать в диапазоне 0..{radix - 1}")
+    if len(digits) < 2 or radix ** len(digits) < FPE_MIN_DOMAIN:
+        raise ValueError(f"Домен слишком мал: нужно не меньше 2 цифр и radix^n >= {FPE_MIN_DOMAIN} "
+                         f"(radix = {radix}, n = {len(digits)})")
+    if len(digits) > max_len:
+        raise ValueError(f"Строка слишком длинная: не больше {max_len} цифр при radix = {radix}")
+
+def ff1(key, tweak, digits, radix, decrypt=False, trace=None):
+    """
+    Шифрование с сохранением формата FF1 (NIST SP 800-38G): 10 раундов
+    несбалансированной сети Фейстеля над строками цифр по основанию radix.
+    
+    Половины A и B складываются с выходом F по модулю radix^m вместо XOR,
+    функция F - CBC-MAC на AES от заголовка P, твика, номера раунда и B.
+    
+    Args:
+        key: Ключ AES (16, 24 или 32 байта)
+        tweak: Твик (список байтов любой длины)
+        digits: Строка цифр (список чисел от 0 до radix-1)
+        radix: Основание системы счисления
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи состояний (название, A, B) по раундам
+    
+    Returns:
+        Строка цифр той же длины
+    """
+    check_fpe_domain(digits, radix, 2 ** 32)
+    round_keys = aes_expand_key(key)
+    n = len(digits)
+    t = len(tweak)
+    u = n // 2
+    v = n - u
+    
+    # b_len - длина в байтах числа из v цифр, d_len - длина выхода F в байтах
+    b_len = ((radix ** v - 1).bit_length() + 7) // 8
+    d_len = 4 * ((b_len + 3) // 4) + 4
+    header = [1, 2, 1] + int_to_vec(radix, 3) + [10, u % 256] + int_to_vec(n, 4) + int_to_vec(t, 4)
+    
+    def round_function(i, half):
+        q = tweak + [0] * ((-t - b_len - 1) % 16) + [i] + int_to_vec(num_radix(half, radix), b_len)
+        # PRF: CBC-MAC с нулевым IV
+        r = [0] * 16
+        message = header + q
+        for j in range(0, len(message), 16):
+            r = aes_encrypt(vec_xor(r, message[j:j + 16]), round_keys)
+        # Выход F растягивается шифрованием R ⊕ [j]^16
+        s = r.copy()
+        for j in range(1, (d_len + 15) // 16):
+            s += aes_encrypt(vec_xor(r, int_to_vec(j, 16)), round_keys)
+        return vec_to_int(s[:d_len])
+    
+    a, b = digits[:u], digits[u:]
+    if trace is not None:
+        trace.append(("Начальный блок", a, b))
+    
+    for step in range(10):
+        i = 9 - step if decrypt else step
+        m = u if i % 2 == 0 else v
+        if decrypt:
+            y = round_function(i, a)
+            c = (num_radix(b, radix) - y) % radix ** m
+            a, b = str_radix(c, radix, m), a
+        else:
+            y = round_function(i, b)
+            c = (num_radix(a, radix) + y) % radix ** m
+            a, b = b, str_radix(c, radix, m)
+        if trace is not None:
+            trace.append((f"Раунд {i + 1}", a, b))
+    
+    return a + b
+
+# Примеры NIST для FF1 (SP 800-38G): ключ, твик, основание, открытый и шифртекст
+FF1_TEST_VECTORS = [
+    ("2b7e151628aed2a6abf7158809cf4f3c", "", 10, "0123456789", "2433477484"),
+    ("2b7e151628aed2a6abf7158809cf4f3c", "39383736353433323130", 10, "0123456789", "6124200773"),
+    ("2b7e151628aed2a6abf7158809cf4f3c", "3737373770717273373737", 36, 
+     "0123456789abcdefghi", "a9tv40mll9kdu509eum"),
+    ("2b7e151628aed2a6abf7158809cf4f3cef4359d8d580aa4f", "", 10, "0123456789", "2830668132"),
+    ("2b7e151628aed2a6abf7158809cf4f3cef4359d8d580aa4f", "39383736353433323130", 10, 
+     "0123456789", "2496655549"),
+    ("2b7e151628aed2a6abf7158809cf4f3cef4359d8d580aa4f", "3737373770717273373737", 36, 
+     "0123456789abcdefghi", "xbj3kv35jrawxv32ysr"),
+    ("2b7e151628aed2a6abf7158809cf4f3cef4359d8d580aa4f7f036d6f04fc6a94", "", 10, 
+     "0123456789", "6657667009"),
+    ("2b7e151628aed2a6abf7158809cf4f3cef4359d8d580aa4f7f036d6f04fc6a94", "39383736353433323130", 10, 
+     "0123456789", "1001623463"),
+    ("2b7e151628aed2a6abf7158809cf4f3cef4359d8d580aa4f7f036d6f04fc6a94", "3737373770717273373737", 36, 
+     "0123456789abcdefghi", "xs8a0azh2avyalyzuwd"),
+]
+
+def ff3_max_len(radix):
+    """Наибольшая длина строки для FF3-1: 2 * floor(log_radix(2^96))"""
+    k = 0
+    while radix ** (k + 1) <= 1 << 96:
+        k += 1
+    return 2 * k
+
+def ff3_rounds(key, tweak_left, tweak_right, digits, radix, decrypt=False, trace=None):
+    """
+    8 раундов сети Фейстеля FF3 над строками цифр (общие для FF3 и FF3-1).
+    
+    Цифры половин берутся в обратном порядке (REV), ключ AES и блоки
+    шифра - с обратным порядком байтов (REVB).
+    
+    Args:
+        key: Ключ AES (16, 24 или 32 байта)
+        tweak_left: Левая половина твика T_L (4 байта)
+        tweak_right: Правая половина твика T_R (4 байта)
+        digits: Строка цифр (список чисел от 0 до radix-1)
+        radix: Основание системы счисления
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи состояний (название, A, B) по раундам
+    
+    Returns:
+        Строка цифр той же длины
+    """
+    check_fpe_domain(digits, radix, ff3_max_len(radix))
+    round_keys = aes_expand_key(key[::-1])
+    n = len(digits)
+    u = (n + 1) // 2
+    v = n - u
+    
+    def round_function(i, half):
+        # Четные раунды используют T_R, нечетные - T_L
+        w = tweak_right if i % 2 == 0 else tweak_left
+        p = vec_xor(w, int_to_vec(i, 4)) + int_to_vec(num_radix(half[::-1], radix), 12)
+        return vec_to_int(aes_encrypt(p[::-1], round_keys)[::-1])
+    
+    a, b = digits[:u], digits[u:]
+    if trace is not None:
+        trace.append(("Начальный блок", a, b))
+    
+    for step in range(8):
+        i = 7 - step if decrypt else step
+        m = u if i % 2 == 0 else v
+        if decrypt:
+            y = round_function(i, a)
+            c = (num_radix(b[::-1], radix) - y) % radix ** m
+            a, b = str_radix(c, radix, m)[::-1], a
+        else:
+            y = round_function(i, b)
+            c = (num_radix(a[::-1], radix) + y) % radix ** m
+            a, b = b, str_radix(c, radix, m)[::-1]
+        if trace is not None:
+            trace.append((f"Раунд {i + 1}", a, b))
+    
+    return a + b
+
+def ff3_1(key, tweak, digits, radix, decrypt=False, trace=None):
+    """
+    Шифрование с сохранением формата FF3-1 (NIST SP 800-38G Rev.1):
+    8 раундов сети Фейстеля с 56-битным твиком.
+    
+    Твик делится на T_L = T[0..27] || 0^4 и T_R = T[32..55] || T[28..31] || 0^4.
+    
+    Args:
+        key: Ключ AES (16, 24 или 32 байта)
+        tweak: Твик ровно из 7 байтов (56 битов)
+        digits: Строка цифр (список чисел от 0 до radix-1)
+        radix: Основание системы счисления
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи состояний (название, A, B) по раундам
+    
+    Returns:
+        Строка цифр той же длины
+    """
+    if len(tweak) != 7:
+        raise ValueError(f"Твик FF3-1 должен иметь длину 56 битов (7 байтов), а не {8 * len(tweak)} битов")
+    tweak_left = tweak[:3] + [tweak[3] & 0xF0]
+    tweak_right = tweak[4:] + [(tweak[3] & 0x0F) << 4]
+    return ff3_rounds(key, tweak_left, tweak_right, digits, radix, decrypt, trace)
+
+# Известные ответы FF3-1 (твик 56 битов); у последнего примера свой алфавит
+FF3_1_TEST_VECTORS = [
+    ("2de79d232df5585d68ce47882ae256d6", "cbd09280979564", 10, "3992520240", "8901801106"),
+    ("01c63017111438f7fc8e24eb16c71ab5", "c4e822dcd09f27", 10, 
+     "60761757463116869318437658042297305934914824457484538562", 
+     "35637144092473838892796702739628394376915177448290847293"),
+    ("db602dff22ed7e84c8d8c865a941a238", "ebefd63bcc2083", 26, 
+     "kkuomenbzqvggfbteqdyanwpmhzdmoicekiihkrm", "belcfahcwwytwrckieymthabgjjfkxtxauipmjja", 
+     "abcdefghijklmnopqrstuvwxyz"),
+]
+
+# Реестр методов FPE: название -> (функция, примеры для проверки)
+FPE_METHODS = {
+    "FF1": (ff1, FF1_TEST_VECTORS),
+    "FF3-1": (ff3_1, FF3_1_TEST_VECTORS),
+}
+
+# Алфавит по умолчанию для оснований до 36: цифры, затем латинские буквы
+FPE_ALPHABET = "0123456789abcdefghijklmnopqrstuvwxyz"
+
+def text_to_digits(text, alphabet):
+    """Переводит строку символов алфавита в список цифр"""
+    digits = []
+    for char in text:
+        if char not in alphabet:
+            raise ValueError(f"Символ '{char}' не входит в алфавит '{alphabet}'")
+        digits.append(alphabet.index(char))
+    return digits
+
+def digits_to_text(digits, alphabet):
+    """Переводит список цифр в строку символов алфавита"""
+    return ''.join(alphabet[d] for d in digits)
+
+def run_fpe_test_vectors(cipher, vectors):
+    """
+    Проверяет реализацию FPE по известным примерам (ключ и твик в HEX).
+    
+    Args:
+        cipher: Функция (key, tweak, digits, radix, decrypt) -> digits
+        vectors: Список примеров (ключ, твик, основание, открытый текст, шифртекст
+            и, если алфавит отличается от FPE_ALPHABET, алфавит)
+    
+    Returns:
+        Список строк с результатами проверки
+    """
+    lines = []
+    for number, (key, tweak, radix, plain, expected, *alphabet) in enumerate(vectors, 1):
+        key, tweak = list(bytes.fromhex(key)), list(bytes.fromhex(tweak))
+        alphabet = alphabet[0] if alphabet else FPE_ALPHABET[:radix]
+        result = digits_to_text(cipher(key, tweak, text_to_digits(plain, alphabet), radix, False), 
+                                alphabet)
+        back = digits_to_text(cipher(key, tweak, text_to_digits(result, alphabet), radix, True), 
+                              alphabet)
+        status = "OK" if result == expected and back == plain else "ОШИБКА"
+        lines.append(f"Пример {number}: {plain} -> {result} (ожидается {expected}) {status}")
+    return lines
+
+def f_masked(right, key, round_func=f, bits=8):
+    """
+    Функция F, выход которой обрезается до младших bits битов: половины
+    сети меньше байта не выходят за свою разрядность.
+    """
+    value = vec_to_int(round_func(right, key)) & ((1 << bits) - 1)
+    return int_to_vec(value, len(right))
+
+def masked_round_funcs(round_func, bits):
+    """Обрезает выход функции F или каждой функции списка до bits битов"""
+    if isinstance(round_func, list):
+        return [masked_round_funcs(fn, bits) for fn in round_func]
+    return functools.partial(f_masked, round_func=round_func, bits=bits)
+
+def domain_half_bits(n):
+    """Разрядность половины сети для домена [0, n): 2h - ближайшая четная разрядность"""
+    return max(1, ((n - 1).bit_length() + 1) // 2)
+
+def cycle_walk(value, n, key, decrypt=False, rounds=10, round_func=f, 
+               schedule=schedule_permute, trace=None):
+    """
+    Шифрует число из произвольного диапазона [0, n) (перестановка домена).
+    
+    Сбалансированная сеть Фейстеля (crypt_block) работает на ближайшей
+    четной разрядности 2h >= разрядности n - 1; если результат выходит
+    за пределы домена, он шифруется повторно (cycle walking), пока
+    не попадет в [0, n). Дешифрование идет по тому же циклу назад.
+    
+    Args:
+        value: Число из диапазона [0, n)
+        n: Размер домена
+        key: Ключ шифрования
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        rounds: Количество раундов сети
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS) или список функций
+        schedule: Алгоритм расписания ключей (см. KEY_SCHEDULES)
+        trace: Список, в который записываются все промежуточные значения
+    
+    Returns:
+        Число из диапазона [0, n)
+    """
+    if n < 2:
+        raise ValueError(f"Домен должен содержать не меньше двух чисел, а не {n}")
+    if not 0 <= value < n:
+        raise ValueError(f"Число {value} не входит в домен [0, {n})")
+    
+    # Разрядность половины и число байтов для ее хранения
+    half_bits = domain_half_bits(n)
+    width = (half_bits + 7) // 8
+    mask = (1 << half_bits) - 1
+    funcs = masked_round_funcs(round_func, half_bits)
+    
+    while True:
+        block = int_to_vec(value >> half_bits, width) + int_to_vec(value & mask, width)
+        block = crypt_block(block, key.copy(), decrypt, rounds, funcs, schedule)
+        value = (vec_to_int(block[:width]) << half_bits) | vec_to_int(block[width:])
+        if trace is not None:
+            trace.append(value)
+        if value < n:
+            return value
+
+def random_permutation(size):
+    """
+    Случайная перестановка блоков size байтов, выбираемая лениво:
+    образ каждого нового блока выбирается случайно среди еще не занятых.
+    
+    Returns:
+        Пара функций (encrypt, decrypt)
+    """
+    forward = {}
+    backward = {}
+    
+    def sample(table, inverse, block):
+        block = tuple(block)
+        if block not in table:
+            image = tuple(os.urandom(size))
+            while image in inverse:
+                image = tuple(os.urandom(size))
+            table[block] = image
+            inverse[image] = block
+        return list(table[block])
+    
+    return (lambda block: sample(forward, backward, block), 
+            lambda block: sample(backward, forward, block))
+
+def distinguisher_cpa(encrypt, decrypt, size):
+    """
+    Различитель для 2 раундов (только запросы на шифрование).
+    
+    Два открытых текста с одинаковой правой половиной R: после двух
+    раундов и финальной перестановки правая половина шифртекста
+    равна L ⊕ F1(R), поэтому XOR правых половин равен L1 ⊕ L2.
+    
+    Returns:
+        True, если оракул признан сетью Фейстеля
+    """
+    half = size // 2
+    left1, left2, right = [list(os.urandom(half)) for _ in range(3)]
+    c1 = encrypt(left1 + right)
+    c2 = encrypt(left2 + right)
+    return vec_xor(c1[half:], c2[half:]) == vec_xor(left1, left2)
+
+def distinguisher_cca(encrypt, decrypt, size):
+    """
+    Различитель для 3 раундов с запросами на дешифрование.
+    
+    Шифруем (L1, R1) и (L2, R1), затем дешифруем второй шифртекст,
+    в котором к левой половине добавлено L1 ⊕ L2: у сети из 3 раундов
+    правая половина ответа равна R1 ⊕ S1 ⊕ S2, где S - правые
+    половины шифртекстов.
+    
+    Returns:
+        True, если оракул признан сетью Фейстеля
+    """
+    half = size // 2
+    left1, left2, right = [list(os.urandom(half)) for _ in range(3)]
+    c1 = encrypt(left1 + right)
+    c2 = encrypt(left2 + right)
+    p = decrypt(vec_xor(c2[:half], vec_xor(left1, left2)) + c2[half:])
+    return p[half:] == vec_xor(right, vec_xor(c1[half:], c2[half:]))
+
+# Различители конструкции Люби-Ракоффа: название -> функция (encrypt, decrypt, size)
+LR_DISTINGUISHERS = {
+    "CPA (атака на 2 раунда)": distinguisher_cpa,
+    "CCA (атака на 3 раунда)": distinguisher_cca,
+}
+
+def luby_rackoff_demo(block_size=32, trials=100, rounds_list=(2, 3, 4), 
+                      schedule=schedule_permute):
+    """
+    Запускает различители против сети Фейстеля с ПСФ HMAC-SHA256
+    и против случайной перестановки.
+    
+    Результаты Люби-Ракоффа: 3 раунда дают псевдослучайную перестановку
+    (CPA-различитель не работает), 4 раунда - сильную псевдослучайную
+    перестановку (не работает и CCA-различитель).
+    
+    Args:
+        block_size: Размер блока в битах
+        trials: Число испытаний для каждой пары (раунды, различитель)
+        rounds_list: Проверяемые числа раундов
+        schedule: Алгоритм расписания ключей (см. KEY_SCHEDULES)
+    
+    Returns:
+        Список пар (название оракула, {различитель: доля успехов})
+    """
+    size = block_bytes(block_size)
+    
+    def run(make_oracle):
+        rates = {}
+        for name, distinguisher in LR_DISTINGUISHERS.items():
+            wins = sum(distinguisher(*make_oracle(), size) for _ in range(trials))
+            rates[name] = wins / trials
+        return rates
+    
+    def feistel_oracle(rounds):
+        # Каждое испытание - новый случайный мастер-ключ
+        key = list(os.urandom(16))
+        return (lambda block: crypt_block(block, key.copy(), False, rounds, f_hmac, schedule), 
+                lambda block: crypt_block(block, key.copy(), True, rounds, f_hmac, schedule))
+    
+    rows = [(f"Сеть Фейстеля, {rounds} раунда", run(lambda: feistel_oracle(rounds))) 
+            for rounds in rounds_list]
+    rows.append(("Случайная перестановка", run(lambda: random_permutation(size))))
+    return rows
+
+def schedule_des(key, rounds, width=6):
+    """
+    Расписание ключей DES: выбор PC-1, циклические сдвиги половин C и D
+    по таблице DES_SHIFTS и выбор PC-2. Ключи раундов всегда 48-битные,
+    параметр width оставлен для совместимости с KEY_SCHEDULES.
+    """
+    if len(key) != 8:
+        raise ValueError(f"Ключ DES должен иметь длину 8 байтов, а не {len(key)}")
+    bits = vec_to_bits(key)
+    cd = [bits[i - 1] for i in DES_PC1]
+    c, d = cd[:28], cd[28:]
+    keys = []
+    for i in range(rounds):
+        shift = DES_SHIFTS[i % 16]
+        c = c[shift:] + c[:shift]
+        d = d[shift:] + d[:shift]
+        keys.append(bits_to_vec([(c + d)[j - 1] for j in DES_PC2]))
+    return keys
+
+def f_des_round(right, key):
+    """
+    Функция F стандарта DES: расширение E 32-битной половины до 48 битов,
+    XOR с 48-битным ключом раунда, S-блоки S1..S8 и перестановка P.
+    """
+    x = vec_xor(des_expand(vec_to_bits(right)), vec_to_bits(key))
+    return permute_bits(bits_to_vec(sbox6_layer(x, DES_SBOXES_FLAT)), DES_P)
+
+def des_crypt_block(block, key, decrypt, trace=None):
+    """
+    Шифрует или дешифрует 64-битный блок DES на общей сети Фейстеля:
+    IP, 16 раундов crypt_round с F стандарта DES, обмен половин и FP.
+    
+    Args:
+        block: Блок из 8 байтов
+        key: Ключ из 8 байтов (биты четности не используются)
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи промежуточных состояний (см. crypt_block)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    if len(block) != 8:
+        raise ValueError(f"Блок DES должен иметь длину 8 байтов, а не {len(block)}")
+    return crypt_block(block, key, decrypt, 16, f_des_round, schedule_des, trace=trace, 
+                       ip=DES_IP, fp=DES_FP, key_width=6)
+
+# Известные ответы DES: ключ, открытый текст, шифртекст
+DES_TEST_VECTORS = [
+    ("133457799bbcdff1", "0123456789abcdef", "85e813540f0ab405"),
+    ("0e329232ea6d0d73", "8787878787878787", "0000000000000000"),
+    ("0123456789abcdef", "4e6f772069732074", "3fa40e8a984d4815"),
+    ("0000000000000000", "0000000000000000", "8ca64de9c1b123a7"),
+    ("ffffffffffffffff", "ffffffffffffffff", "7359b2163e4edc58"),
+    ("3000000000000000", "1000000000000001", "958e6e627a05557b"),
+    ("1111111111111111", "1111111111111111", "f40379ab9e0ec533"),
+    ("0123456789abcdef", "1111111111111111", "17668dfc7292532d"),
+]
+
+def run_test_vectors(crypt, vectors):
+    """
+    Проверяет блочный шифр по известным ответам (ключ, открытый текст
+    и шифртекст в HEX): шифрование должно дать шифртекст, а его
+    дешифрование - исходный текст.
+    
+    Args:
+        crypt: Функция (block, key, decrypt) -> block
+        vectors: Список примеров (ключ, открытый текст, шифртекст)
+    
+    Returns:
+        Список строк с результатами проверки
+    """
+    lines = []
+    for number, (key, plain, expected) in enumerate(vectors, 1):
+        key, plain_block = list(bytes.fromhex(key)), list(bytes.fromhex(plain))
+        result = bytes(crypt(plain_block.copy(), key.copy(), False)).hex()
+        back = bytes(crypt(list(bytes.fromhex(result)), key.copy(), True)).hex()
+        status = "OK" if result == expected and back == plain else "ОШИБКА"
+        lines.append(f"Пример {number}: {plain} -> {result} (ожидается {expected}) {status}")
+    return lines
+
+def magma_crypt_block(block, key, decrypt, trace=None):
+    """
+    Шифрует или дешифрует 64-битный блок шифром "Магма" (ГОСТ Р 34.12-2015,
+    ранее ГОСТ 28147-89): 32 раунда crypt_round с 32-битными половинами,
+    функцией g[k] (сложение по модулю 2^32, узлы замены param-Z, сдвиг
+    на 11 битов) и ключами K1..K8 трижды, затем K8..K1. Последний раунд G*
+    не меняет половины местами - это и есть финальная перестановка сети.
+    
+    Args:
+        block: Блок из 8 байтов (a1 || a0, старшие байты первыми)
+        key: Ключ из 32 байтов
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи промежуточных состояний; раунды подписаны
+            как в стандарте: G[K1], ..., G[K31], G*[K32]
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    if len(block) != 8:
+        raise ValueError(f"Блок Магмы должен иметь длину 8 байтов, а не {len(block)}")
+    if len(key) != 32:
+        raise ValueError(f"Ключ Магмы должен иметь длину 32 байта, а не {len(key)}")
+    states = [] if trace is not None else None
+    block = crypt_block(block, key, decrypt, 32, f_gost, schedule_gost, trace=states)
+    if trace is not None:
+        for name, state, round_key in states:
+            if name.startswith("Раунд "):
+                i = int(name.split()[1])
+                k = 33 - i if decrypt else i
+                if i < 32:
+                    name = f"G[K{k}]"
+                else:
+                    # G* оставляет половины на местах: его выход - результат шифра
+                    name, state = f"G*[K{k}]", block.copy()
+            trace.append((name, state, round_key))
+    return block
+
+# Контрольный пример ГОСТ Р 34.12-2015, приложение А.2
+MAGMA_TEST_VECTORS = [
+    ("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff", 
+     "fedcba9876543210", "4ee901e5c2d8ca3d"),
+]
+
+def pi_hex_words(count):
+    """
+    Вычисляет первые count 32-битных слов дробной части числа π
+    в целых числах по формуле Мэчина: π = 16·arctg(1/5) - 4·arctg(1/239).
+    
+    Args:
+        count: Число слов
+    
+    Returns:
+        Список слов (0x243f6a88, 0x85a308d3, ...)
+    """
+    # Запасные 64 бита поглощают ошибки округления при делении
+    one = 1 << (32 * count + 64)
+    
+    def arctan_inv(x):
+        # arctg(1/x) = 1/x - 1/(3x^3) + 1/(5x^5) - ...
+        total = term = one // x
+        n = 1
+        while term:
+            term //= x * x
+            total += (-1) ** n * (term // (2 * n + 1))
+            n += 1
+        return total
+    
+    frac = ((16 * arctan_inv(5) - 4 * arctan_inv(239)) % one) >> 64
+    return [(frac >> (32 * (count - 1 - i))) & 0xffffffff for i in range(count)]
+
+# Начальные P-массив (18 слов) и S-блоки (4 по 256 слов) Blowfish - цифры π
+BLOWFISH_PI = pi_hex_words(18 + 4 * 256)
+
+def half_swap_table(bits):
+    """Таблица перестановки, меняющей местами половины блока из bits битов"""
+    return list(range(bits // 2 + 1, bits + 1)) + list(range(1, bits // 2 + 1))
+
+# Перестановка, меняющая местами 32-битные половины блока
+HALF_SWAP_64 = half_swap_table(64)
+
+def f_blowfish(right, key, sboxes):
+    """
+    Функция F Blowfish с ключом раунда: ((S1[a] + S2[b]) ⊕ S3[c]) + S4[d]
+    по модулю 2^32 (a..d - байты половины, старший первым), затем XOR
+    с элементом P-массива. Ключевыми здесь являются и сами S-блоки.
+    """
+    a, b, c, d = right
+    x = ((sboxes[0][a] + sboxes[1][b]) % (1 << 32) ^ sboxes[2][c]) + sboxes[3][d]
+    return vec_xor(int_to_vec(x % (1 << 32), 4), key)
+
+def blowfish_encipher(block, p_array, sboxes, decrypt=False, trace=None):
+    """
+    Шифрует блок Blowfish с заданными P-массивом и S-блоками на общей
+    сети Фейстеля. Раунд Blowfish (L ⊕= P_i, R ⊕= F(L), обмен) переписан
+    для crypt_block: половины меняются местами перестановками IP/FP,
+    P1 и P18 становятся ключами отбеливания левой половины, P2..P17 -
+    ключами раундов функции F'(x) = F(x) ⊕ P_i.
+    
+    Args:
+        block: Блок из 8 байтов
+        p_array: P-массив из 18 слов
+        sboxes: Четыре S-блока по 256 слов
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи промежуточных состояний (см. crypt_block)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    pre = int_to_vec(p_array[0], 4) + [0] * 4
+    post = int_to_vec(p_array[17], 4) + [0] * 4
+    round_keys = [int_to_vec(word, 4) for word in p_array[1:17]]
+    return crypt_block(block, [], decrypt, 16, functools.partial(f_blowfish, sboxes=sboxes), 
+                       lambda key, rounds, width: [k.copy() for k in round_keys], trace=trace, 
+                       ip=HALF_SWAP_64, fp=HALF_SWAP_64, whitening=(pre, post))
+
+def blowfish_key_setup(key, trace=None):
+    """
+    Расписание ключей Blowfish: P-массив складывается по XOR с циклически
+    повторенным ключом, затем нулевой блок многократно шифруется, и каждый
+    результат заменяет очередные два слова P-массива, а затем S-блоков -
+    всего 521 шифрование. В отличие от keys_gen, ключ определяет не только
+    ключи раундов, но и S-блоки функции F.
+    
+    Args:
+        key: Ключ длиной от 4 до 56 байтов
+        trace: Список для записи снимков (этап, P-массив, S-блоки) для визуализации
+    
+    Returns:
+        Пара (P-массив, S-блоки)
+    """
+    if not 4 <= len(key) <= 56:
+        raise ValueError(f"Ключ Blowfish должен иметь длину от 4 до 56 байтов, а не {len(key)}")
+    p_array = BLOWFISH_PI[:18]
+    sboxes = [BLOWFISH_PI[18 + 256 * i:18 + 256 * (i + 1)] for i in range(4)]
+    
+    def snapshot(name):
+        if trace is not None:
+            trace.append((name, p_array.copy(), [box.copy() for box in sboxes]))
+    
+    snapshot("Цифры π")
+    for i in range(18):
+        p_array[i] ^= vec_to_int([key[(4 * i + j) % len(key)] for j in range(4)])
+    snapshot("P XOR ключ")
+    
+    # Каждое шифрование использует уже замененные слова
+    block = [0] * 8
+    for name, table in [("P", p_array)] + [(f"S{i + 1}", box) for i, box in enumerate(sboxes)]:
+        for i in range(0, len(table), 2):
+            block = blowfish_encipher(block, p_array, sboxes)
+            table[i], table[i + 1] = vec_to_int(block[:4]), vec_to_int(block[4:])
+        snapshot(f"{name} заменен")
+    return p_array, sboxes
+
+@functools.lru_cache(maxsize=16)
+def blowfish_keys(key):
+    """Кэширует дорогое расписание ключей Blowfish для ключа key (bytes)"""
+    return blowfish_key_setup(list(key))
+
+def blowfish_crypt_block(block, key, decrypt, trace=None):
+    """
+    Шифрует или дешифрует 64-битный блок Blowfish: 16 раундов
+    с P-массивом и S-блоками, зависящими от ключа.
+    
+    Args:
+        block: Блок из 8 байтов
+        key: Ключ длиной от 4 до 56 байтов
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи промежуточных состояний (см. crypt_block)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    if len(block) != 8:
+        raise ValueError(f"Блок Blowfish должен иметь длину 8 байтов, а не {len(block)}")
+    p_array, sboxes = blowfish_keys(bytes(key))
+    return blowfish_encipher(block, p_array, sboxes, decrypt, trace)
+
+# Известные ответы Blowfish (Eric Young): ключ, открытый текст, шифртекст
+BLOWFISH_TEST_VECTORS = [
+    ("0000000000000000", "0000000000000000", "4ef997456198dd78"),
+    ("ffffffffffffffff", "ffffffffffffffff", "51866fd5b85ecb8a"),
+    ("3000000000000000", "1000000000000001", "7d856f9a613063f2"),
+    ("1111111111111111", "1111111111111111", "2466dd878b963c9d"),
+    ("0123456789abcdef", "1111111111111111", "61f9c3802281b096"),
+    ("1111111111111111", "0123456789abcdef", "7d0cc630afda1ec7"),
+    ("0000000000000000", "0000000000000000", "4ef997456198dd78"),
+    ("fedcba9876543210", "0123456789abcdef", "0aceab0fc6a0a28d"),
+    ("7ca110454a1a6e57", "01a1d6d039776742", "59c68245eb05282b"),
+    ("0131d9619dc1376e", "5cd54ca83def57da", "b1b8cc0b250f09a0"),
+    ("07a1133e4a0b2686", "0248d43806f67172", "1730e5778bea1da4"),
+    ("3849674c2602319e", "51454b582ddf440a", "a25e7856cf2651eb"),
+    ("04b915ba43feb5b6", "42fd443059577fa2", "353882b109ce8f1a"),
+    ("0113b970fd34f2ce", "059b5e0851cf143a", "48f4d0884c379918"),
+    ("0170f175468fb5e6", "0756d8e0774761d2", "432193b78951fc98"),
+    ("43297fad38e373fe", "762514b829bf486a", "13f04154d69d1ae5"),
+    ("07a7137045da2a16", "3bdd119049372802", "2eedda93ffd39c79"),
+    ("04689104c2fd3b2f", "26955f6835af609a", "d887e0393c2da6e3"),
+    ("37d06bb516cb7546", "164d5e404f275232", "5f99d04f5b163969"),
+    ("1f08260d1ac2465e", "6b056e18759f5cca", "4a057a3b24d3977b"),
+    ("584023641aba6176", "004bd6ef09176062", "452031c1e4fada8e"),
+    ("025816164629b007", "480d39006ee762f2", "7555ae39f59b87bd"),
+    ("49793ebc79b3258f", "437540c8698f3cfa", "53c55f9cb49fc019"),
+    ("4fb05e1515ab73a7", "072d43a077075292", "7a8e7bfa937e89a3"),
+    ("49e95d6d4ca229bf", "02fe55778117f12a", "cf9c5d7a4986adb5"),
+    ("018310dc409b26d6", "1d9d5c5018f728c2", "d1abb290658bc778"),
+    ("1c587f1c13924fef", "305532286d6f295a", "55cb3774d13ef201"),
+    ("0101010101010101", "0123456789abcdef", "fa34ec4847b268b2"),
+    ("1f1f1f1f0e0e0e0e", "0123456789abcdef", "a790795108ea3cae"),
+    ("e0fee0fef1fef1fe", "0123456789abcdef", "c39e072d9fac631d"),
+    ("0000000000000000", "ffffffffffffffff", "014933e0cdaff6e4"),
+    ("ffffffffffffffff", "0000000000000000", "f21e9a77b71c49bc"),
+    ("0123456789abcdef", "0000000000000000", "245946885754369a"),
+    ("fedcba9876543210", "ffffffffffffffff", "6b5c5a9c5d9e0a5a"),
+]
+
+# Константа δ TEA и XTEA: целая часть (√5 - 1) · 2^31
+TEA_DELTA = 0x9e3779b9
+WORD_MASK = 0xffffffff
+
+def tea_mix(x, total, ka, kb):
+    """Функция F TEA: ((x << 4) + ka) ⊕ (x + sum) ⊕ ((x >> 5) + kb) по модулю 2^32"""
+    return ((((x << 4) + ka) & WORD_MASK) ^ ((x + total) & WORD_MASK) ^ ((x >> 5) + kb)) & WORD_MASK
+
+def xtea_mix(x, total, k):
+    """Функция F XTEA: (((x << 4) ⊕ (x >> 5)) + x) ⊕ (sum + k) по модулю 2^32"""
+    return (((((x << 4) & WORD_MASK) ^ (x >> 5)) + x) ^ (total + k)) & WORD_MASK
+
+def tea_words(block, key, name):
+    """Разбирает 64-битный блок и 128-битный ключ TEA/XTEA на 32-битные слова (big-endian)"""
+    if len(block) != 8:
+        raise ValueError(f"Блок {name} должен иметь длину 8 байтов, а не {len(block)}")
+    if len(key) != 16:
+        raise ValueError(f"Ключ {name} должен иметь длину 16 байтов, а не {len(key)}")
+    return [vec_to_int(block[:4]), vec_to_int(block[4:])], [vec_to_int(key[i:i + 4]) for i in range(0, 16, 4)]
+
+def tea_cycle_keys(key, cycles=32):
+    """Значения sum = i·δ по циклам TEA - единственная часть раунда, меняющаяся от цикла к циклу"""
+    return [int_to_vec(TEA_DELTA * (i + 1) & WORD_MASK, 4) for i in range(cycles)]
+
+def xtea_cycle_keys(key, cycles=32):
+    """Слова ключа k[sum & 3] и k[(sum >> 11) & 3], выбираемые XTEA в каждом цикле"""
+    words = [key[i:i + 4] for i in range(0, 16, 4)]
+    return [words[TEA_DELTA * i & 3] + words[(TEA_DELTA * (i + 1) & WORD_MASK) >> 11 & 3] 
+            for i in range(cycles)]
+
+def tea_crypt_block(block, key, decrypt, trace=None, cycles=32):
+    """
+    Шифрует или дешифрует 64-битный блок TEA. Цикл TEA - два раунда
+    сети Фейстеля над половинами v0, v1, но F прибавляется по модулю 2^32,
+    а не по XOR, поэтому crypt_round не подходит: дешифрование вычитает F
+    в обратном порядке. Состояния записываются в trace по циклам
+    в формате crypt_block.
+    
+    Args:
+        block: Блок из 8 байтов (v0 || v1, big-endian)
+        key: Ключ из 16 байтов (k0..k3)
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи промежуточных состояний или None
+        cycles: Число циклов (32 в стандартном TEA)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    (v0, v1), k = tea_words(block, key, "TEA")
+    if trace is not None:
+        trace.append(("Начальный блок", block.copy(), None))
+    for i in range(cycles):
+        if decrypt:
+            total = TEA_DELTA * (cycles - i) & WORD_MASK
+            v1 = (v1 - tea_mix(v0, total, k[2], k[3])) & WORD_MASK
+            v0 = (v0 - tea_mix(v1, total, k[0], k[1])) & WORD_MASK
+            name = f"v1 -= F(v0), v0 -= F(v1), sum = {total:08x}"
+        else:
+            total = TEA_DELTA * (i + 1) & WORD_MASK
+            v0 = (v0 + tea_mix(v1, total, k[0], k[1])) & WORD_MASK
+            v1 = (v1 + tea_mix(v0, total, k[2], k[3])) & WORD_MASK
+            name = f"v0 += F(v1), v1 += F(v0), sum = {total:08x}"
+        if trace is not None:
+            trace.append((name, int_to_vec(v0, 4) + int_to_vec(v1, 4), int_to_vec(total, 4)))
+    block = int_to_vec(v0, 4) + int_to_vec(v1, 4)
+    if trace is not None:
+        trace.append(("Финальный результат", block.copy(), None))
+    return block
+
+def xtea_crypt_block(block, key, decrypt, trace=None, cycles=32):
+    """
+    Шифрует или дешифрует 64-битный блок XTEA: как TEA, но F смешивает
+    сдвиги половины с ней самой, а слово ключа выбирается по битам sum,
+    причем второй раунд цикла использует уже увеличенное sum.
+    
+    Args:
+        block: Блок из 8 байтов (v0 || v1, big-endian)
+        key: Ключ из 16 байтов (k0..k3)
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи промежуточных состояний или None
+        cycles: Число циклов (32 в стандартном XTEA)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    (v0, v1), k = tea_words(block, key, "XTEA")
+    if trace is not None:
+        trace.append(("Начальный блок", block.copy(), None))
+    for i in range(cycles):
+        # Цикл шифрования j использует sum = j·δ в первом раунде и (j + 1)·δ во втором
+        j = cycles - 1 - i if decrypt else i
+        first, second = TEA_DELTA * j & WORD_MASK, TEA_DELTA * (j + 1) & WORD_MASK
+        a, b = first & 3, second >> 11 & 3
+        if decrypt:
+            v1 = (v1 - xtea_mix(v0, second, k[b])) & WORD_MASK
+            v0 = (v0 - xtea_mix(v1, first, k[a])) & WORD_MASK
+            name = f"v1 -= F(v0, k{b}), v0 -= F(v1, k{a}), sum = {second:08x}"
+        else:
+            v0 = (v0 + xtea_mix(v1, first, k[a])) & WORD_MASK
+            v1 = (v1 + xtea_mix(v0, second, k[b])) & WORD_MASK
+            name = f"v0 += F(v1, k{a}), v1 += F(v0, k{b}), sum = {second:08x}"
+        if trace is not None:
+            trace.append((name, int_to_vec(v0, 4) + int_to_vec(v1, 4), 
+                          int_to_vec(k[a], 4) + int_to_vec(k[b], 4)))
+    block = int_to_vec(v0, 4) + int_to_vec(v1, 4)
+    if trace is not None:
+        trace.append(("Финальный результат", block.copy(), None))
+    return block
+
+# Известные ответы TEA и XTEA: ключ, открытый текст, шифртекст
+TEA_TEST_VECTORS = [
+    ("00000000000000000000000000000000", "0000000000000000", "41ea3a0a94baa940"),
+    ("00000000000000000000000000000000", "0102030405060708", "6a2f9cf3fccf3c55"),
+    ("0123456712345678234567893456789a", "0000000000000000", "34e943b0900f5dcb"),
+    ("0123456712345678234567893456789a", "0102030405060708", "773dc179878a81c0"),
+]
+XTEA_TEST_VECTORS = [
+    ("00000000000000000000000000000000", "0000000000000000", "dee9d4d8f7131ed9"),
+    ("00000000000000000000000000000000", "0102030405060708", "065c1b8975c6a816"),
+    ("0123456712345678234567893456789a", "0000000000000000", "1ff9a0261ac64264"),
+    ("0123456712345678234567893456789a", "0102030405060708", "8c67155b2ef91ead"),
+]
+
+# Последовательности z0..z4 расписания ключей Simon (период 62)
+SIMON_Z = [
+    "11111010001001010110000111001101111101000100101011000011100110",
+    "10001110111110010011000010110101000111011111001001100001011010",
+    "10101111011100000011010010011000101000010001111110010110110011",
+    "11011011101011000110010111100000010010001010011100110100001111",
+    "11010001111001101011011000100000010111000011001010010011101111",
+]
+
+# Семейство Simon: (размер блока 2n, размер ключа mn) в битах -> (число раундов, номер z)
+SIMON_PARAMS = {
+    (32, 64): (32, 0),
+    (48, 72): (36, 0),
+    (48, 96): (36, 1),
+    (64, 96): (42, 2),
+    (64, 128): (44, 3),
+    (96, 96): (52, 2),
+    (96, 144): (54, 3),
+    (128, 128): (68, 2),
+    (128, 192): (69, 3),
+    (128, 256): (72, 4),
+}
+
+def simon_params(block_size, key_size):
+    """Число раундов и номер последовательности z для Simon с блоком и ключом в битах"""
+    if (block_size, key_size) not in SIMON_PARAMS:
+        sizes = ', '.join(f"{b}/{k}" for b, k in SIMON_PARAMS)
+        raise ValueError(f"Simon{block_size}/{key_size} не входит в семейство ({sizes})")
+    return SIMON_PARAMS[(block_size, key_size)]
+
+def schedule_simon(key, rounds, width):
+    """
+    Расписание ключей Simon: ключ из m = 2, 3 или 4 слов по width байтов
+    (старшее слово k_(m-1) первым) дополняется по рекурсии
+    k_i = ¬k_(i-m) ⊕ 3 ⊕ z_j[(i - m) mod 62] ⊕ (I ⊕ S^-1)(S^-3 k_(i-1) [⊕ k_(i-3) при m = 4]).
+    """
+    bits = 8 * width
+    m = len(key) // width
+    _, z = simon_params(16 * width, 8 * len(key))
+    mask = (1 << bits) - 1
+    words = [vec_to_int(key[i:i + width]) for i in range(0, len(key), width)][::-1]
+    for i in range(m, rounds):
+        tmp = rotl(words[i - 1], -3, bits)
+        if m == 4:
+            tmp ^= words[i - 3]
+        tmp ^= rotl(tmp, -1, bits)
+        words.append(~words[i - m] & mask ^ tmp ^ int(SIMON_Z[z][(i - m) % 62]) ^ 3)
+    return [int_to_vec(word, width) for word in words[:rounds]]
+
+def simon_crypt_block(block, key, decrypt, trace=None):
+    """
+    Шифрует или дешифрует блок шифром Simon 2n/mn: раунд
+    (x, y) -> (y ⊕ F(x) ⊕ k_i, x) - это crypt_round с F = f_simon.
+    Функция F вычисляется от левой половины x, а crypt_round - от правой,
+    поэтому половины меняются местами начальной перестановкой, а конечная
+    перестановка (после возврата половин на место) не нужна.
+    
+    Args:
+        block: Блок x || y из 4..16 байтов (слова big-endian)
+        key: Ключ k_(m-1) || ... || k_0 из m слов
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        trace: Список для записи промежуточных состояний (см. crypt_block)
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    bits = 8 * len(block)
+    rounds, _ = simon_params(bits, 8 * len(key))
+    identity = list(range(1, bits + 1))
+    states = [] if trace is not None else None
+    block = crypt_block(block, key, decrypt, rounds, f_simon, schedule_simon, trace=states, 
+                        ip=half_swap_table(bits), fp=identity)
+    if trace is not None:
+        # Тождественную перестановку не показываем
+        trace += [state for state in states if state[2] != identity]
+    return block
+
+# Примеры из описания Simon (ключ, открытый текст, шифртекст; слова старшими первыми)
+SIMON_TEST_VECTORS = {
+    (32, 64): ("1918111009080100", "65656877", "c69be9bb"),
+    (48, 72): ("1211100a0908020100", "6120676e696c", "dae5ac292cac"),
+    (48, 96): ("1a19181211100a0908020100", "72696320646e", "6e06a5acf156"),
+    (64, 96): ("131211100b0a090803020100", "6f7220676e696c63", "5ca2e27f111a8fc8"),
+    (64, 128): ("1b1a1918131211100b0a090803020100", "656b696c20646e75", "44c8fc20b9dfa07a"),
+    (96, 96): ("0d0c0b0a0908050403020100", "2072616c6c69702065687420", "602807a462b469063d8ff082"),
+    (96, 144): ("1514131211100d0c0b0a0908050403020100", "74616874207473756420666f", 
+                "ecad1c6c451e3f59c5db1ae9"),
+    (128, 128): ("0f0e0d0c0b0a09080706050403020100", "63736564207372656c6c657661727420", 
+                 "49681b1e1e54fe3f65aa832af84e0bbc"),
+    (128, 192): ("17161514131211100f0e0d0c0b0a09080706050403020100", 
+                 "206572656874206e6568772065626972", "c4ac61effcdc0d4f6c9c8d6e2597b85b"),
+    (128, 256): ("1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100", 
+                 "74206e69206d6f6f6d69732061207369", "8d2b5579afc8a3a03bf72a87efe7b868"),
+}
+
+# Реестр стандартных шифров на общей сети Фейстеля: название -> параметры
+# crypt(block, key, decrypt, trace), размер блока в битах, допустимые длины ключа
+# в байтах, число раундов, ключи раундов round_keys(key), известные ответы tests,
+# если не все биты ключа значимы, длина ключа в битах key_bits(key) и, если ключ
+# задает не только ключи раундов, расписание key_setup(key, trace) для визуализации
+CIPHERS = {
+    "DES": {
+        "crypt": des_crypt_block,
+        "block_size": 64,
+        "key_sizes": [8],
+        "rounds": 16,
+        "round_keys": lambda key: schedule_des(key, 16),
+        "tests": DES_TEST_VECTORS,
+        "key_bits": lambda key: 56,
+    },
+    "Магма": {
+        "crypt": magma_crypt_block,
+        "block_size": 64,
+        "key_sizes": [32],
+        "rounds": 32,
+        "round_keys": lambda key: schedule_gost(key, 32, 4),
+        "tests": MAGMA_TEST_VECTORS,
+    },
+    "Blowfish": {
+        "crypt": blowfish_crypt_block,
+        "block_size": 64,
+        "key_sizes": list(range(4, 57)),
+        "rounds": 16,
+        "round_keys": lambda key: [int_to_vec(word, 4) for word in blowfish_keys(bytes(key))[0]],
+        "tests": BLOWFISH_TEST_VECTORS,
+        "key_setup": blowfish_key_setup,
+    },
+    "TEA": {
+        "crypt": tea_crypt_block,
+        "block_size": 64,
+        "key_sizes": [16],
+        "rounds": 32,
+        "round_keys": tea_cycle_keys,
+        "tests": TEA_TEST_VECTORS,
+    },
+    "XTEA": {
+        "crypt": xtea_crypt_block,
+        "block_size": 64,
+        "key_sizes": [16],
+        "rounds": 32,
+        "round_keys": xtea_cycle_keys,
+        "tests": XTEA_TEST_VECTORS,
+    },
+}
+
+# Все размеры семейства Simon - отдельные шифры реестра
+for (simon_block, simon_key), (simon_rounds, _) in SIMON_PARAMS.items():
+    CIPHERS[f"Simon{simon_block}/{simon_key}"] = {
+        "crypt": simon_crypt_block,
+        "block_size": simon_block,
+        "key_sizes": [simon_key // 8],
+        "rounds": simon_rounds,
+        "round_keys": lambda key, rounds=simon_rounds, width=simon_block // 16: 
+            schedule_simon(key, rounds, width),
+        "tests": [SIMON_TEST_VECTORS[(simon_block, simon_key)]],
+    }
+
+# Допустимое число независимых ключей каскада EDE
+CASCADE_KEYS = [2, 3]
+
+def cascade_passes(count, decrypt):
+    """
+    Порядок проходов каскада EDE: E_k1, D_k2, E_k3 при шифровании и
+    D_k3, E_k2, D_k1 при дешифровании. С двумя ключами k3 = k1.
+    
+    Args:
+        count: Число независимых ключей (2 или 3)
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+    
+    Returns:
+        Список пар (номер ключа с нуля, флаг дешифрования прохода)
+    """
+    if count not in CASCADE_KEYS:
+        raise ValueError(f"Каскад EDE использует 2 или 3 ключа, а не {count}")
+    passes = [(0, False), (1, True), (count - 1 if count == 3 else 0, False)]
+    if decrypt:
+        passes = [(index, not pass_decrypt) for index, pass_decrypt in reversed(passes)]
+    return passes
+
+def cascade_pass_name(index, decrypt):
+    """Обозначение прохода каскада, например E_k1 или D_k2"""
+    return f"{'D' if decrypt else 'E'}_k{index + 1}"
+
+def crypt_cascade(block, keys, decrypt, crypt):
+    """
+    Шифрует или дешифрует блок каскадом EDE из трех проходов блочного шифра.
+    
+    Args:
+        block: Исходный блок
+        keys: Ключи k1, k2 и, для трехключевого каскада, k3
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        crypt: Функция прохода (block, key, decrypt) -> block,
+               например crypt_block с фиксированными параметрами сети
+    
+    Returns:
+        Зашифрованный или дешифрованный блок
+    """
+    for index, pass_decrypt in cascade_passes(len(keys), decrypt):
+        block = crypt(block, keys[index].copy(), pass_decrypt)
+    return block
+
+def cascade_key_bits(bits):
+    """
+    Оценивает длину ключа каскада EDE. Атака "встреча посередине" делит
+    цепочку проходов на две части и перебирает их ключи независимо, поэтому
+    трехключевой каскад стоит не дороже перебора большей из частей.
+    В двухключевом каскаде k1 стоит по обе стороны любого разреза,
+    и эта атака к нему напрямую не применима.
+    
+    Args:
+        bits: Длины ключей k1, k2[, k3] в битах
+    
+    Returns:
+        Пара (номинальная длина ключа, стойкость против встречи посередине) в битах
+    """
+    nominal = sum(bits)
+    if len(bits) == 3:
+        return nominal, min(max(bits[0], bits[1] + bits[2]), max(bits[0] + bits[1], bits[2]))
+    return nominal, nominal
+
+def cipher_key_bits(key, cipher=None):
+    """Длина ключа в битах; для стандартного шифра учитываются только значимые биты"""
+    if cipher is not None and "key_bits" in CIPHERS[cipher]:
+        return CIPHERS[cipher]["key_bits"](key)
+    return 8 * len(key)
+
+# Допустимые размеры блока в битах
+BLOCK_SIZES = [32, 64, 128]
+
+def block_bytes(block_size):
+    """
+    Переводит размер блока из битов в байты.
+    
+    Args:
+        block_size: Размер блока в битах
+    
+    Returns:
+        Размер блока в байтах
+    """
+    if block_size % 16 != 0 or block_size <= 0:
+        raise ValueError(f"Размер блока {block_size} бит должен быть положительным и кратным 16")
+    return block_size // 8
+
+def split_blocks(data, block_size):
+    """
+    Разбивает сообщение на блоки фиксированного размера.
+    
+    Args:
+        data: Сообщение (список байтов), длина которого кратна размеру блока
+        block_size: Размер блока в битах
+    
+    Returns:
+        Список блоков
+    """
+    size = block_bytes(block_size)
+    if len(data) % size != 0:
+        raise ValueError(f"Длина сообщения ({len(data)} байт) не кратна размеру блока ({size} байт)")
+    return [data[i:i + size] for i in range(0, len(data), size)]
+
+class PaddingError(ValueError):
+    """Ошибка проверки дополнения при дешифровании"""
+
+def pad_pkcs7(data, size):
+    """PKCS#7: каждый байт дополнения равен длине дополнения"""
+    n = size - len(data) % size
+    return data + [n] * n
+
+def unpad_pkcs7(data, size):
+    n = data[-1]
+    if not 1 <= n <= size or data[-n:] != [n] * n:
+        raise PaddingError("Некорректное дополнение PKCS#7")
+    return data[:-n]
+
+def pad_iso7816(data, size):
+    """ISO/IEC 7816-4: байт 0x80, за которым следуют нулевые байты"""
+    n = size - len(data) % size
+    return data + [0x80] + [0] * (n - 1)
+
+def unpad_iso7816(data, size):
+    n = 1
+    while n <= min(size, len(data)) and data[-n] == 0:
+        n += 1
+    if n > min(size, len(data)) or data[-n] != 0x80:
+        raise PaddingError("Некорректное дополнение ISO/IEC 7816-4: не найден маркер 0x80")
+    return data[:-n]
+
+def pad_x923(data, size):
+    """ANSI X.923: нулевые байты, последний байт равен длине дополнения"""
+    n = size - len(data) % size
+    return data + [0] * (n - 1) + [n]
+
+def unpad_x923(data, size):
+    n = data[-1]
+    if not 1 <= n <= size or data[-n:-1] != [0] * (n - 1):
+        raise PaddingError("Некорректное дополнение ANSI X.923")
+    return data[:-n]
+
+def pad_iso10126(data, size):
+    """ISO 10126: случайные байты, последний байт равен длине дополнения"""
+    n = size - len(data) % size
+    return data + list(os.urandom(n - 1)) + [n]
+
+def unpad_iso10126(data, size):
+    n = data[-1]
+    if not 1 <= n <= size:
+        raise PaddingError("Некорректное дополнение ISO 10126")
+    return data[:-n]
+
+# Схемы дополнения: название -> (дополнение, проверка и удаление)
+PADDING_SCHEMES = {
+    "PKCS#7": (pad_pkcs7, unpad_pkcs7),
+    "ISO/IEC 7816-4": (pad_iso7816, unpad_iso7816),
+    "ANSI X.923": (pad_x923, unpad_x923),
+    "ISO 10126": (pad_iso10126, unpad_iso10126),
+}
+
+def pad_message(data, block_size, scheme):
+    """
+    Дополняет сообщение до длины, кратной размеру блока.
+    
+    Дополнение добавляется всегда, даже если длина уже кратна блоку,
+    чтобы при дешифровании его можно было однозначно удалить.
+    
+    Args:
+        data: Сообщение (список байтов)
+        block_size: Размер блока в битах
+        scheme: Название схемы дополнения из PADDING_SCHEMES
+    
+    Returns:
+        Дополненное сообщение
+    """
+    pad, _ = PADDING_SCHEMES[scheme]
+    return pad(data, block_bytes(block_size))
+
+def unpad_message(data, block_size, scheme):
+    """
+    Проверяет и удаляет дополнение после дешифрования.
+    
+    Args:
+        data: Дешифрованное сообщение (список байтов)
+        block_size: Размер блока в битах
+        scheme: Название схемы дополнения из PADDING_SCHEMES
+    
+    Returns:
+        Сообщение без дополнения
+    
+    Raises:
+        PaddingError: если дополнение не соответствует схеме
+    """
+    size = block_bytes(block_size)
+    if not data or len(data) % size != 0:
+        raise PaddingError(f"Длина сообщения ({len(data)} байт) не кратна размеру блока ({size} байт)")
+    _, unpad = PADDING_SCHEMES[scheme]
+    return unpad(data, size)
+
+def increment_counter(block):
+    """Увеличивает блок-счетчик на единицу (big-endian, по модулю 2^n)"""
+    size = len(block)
+    value = (int.from_bytes(bytes(block), 'big') + 1) % (1 << (8 * size))
+    return list(value.to_bytes(size, 'big'))
+
+def mode_ecb(blocks, cipher, iv, decrypt):
+    """ECB: каждый блок шифруется независимо"""
+    return [cipher(block, decrypt) for block in blocks]
+
+def mode_cbc(blocks, cipher, iv, decrypt):
+    """CBC: перед шифрованием блок складывается с предыдущим блоком шифртекста"""
+    result = []
+    prev = iv
+    for block in blocks:
+        if decrypt:
+            result.append(vec_xor(cipher(block, True), prev))
+            prev = block
+        else:
+            prev = cipher(vec_xor(block, prev), False)
+            result.append(prev)
+    return result
+
+def mode_cfb(blocks, cipher, iv, decrypt):
+    """CFB: гамма получается шифрованием предыдущего блока шифртекста"""
+    result = []
+    prev = iv
+    for block in blocks:
+        out = vec_xor(block, cipher(prev, False)[:len(block)])
+        prev = block if decrypt else out
+        result.append(out)
+    return result
+
+def mode_ofb(blocks, cipher, iv, decrypt):
+    """OFB: гамма получается многократным шифрованием вектора инициализации"""
+    result = []
+    gamma = iv
+    for block in blocks:
+        gamma = cipher(gamma, False)
+        result.append(vec_xor(block, gamma[:len(block)]))
+    return result
+
+def mode_ctr(blocks, cipher, iv, decrypt):
+    """CTR: гамма получается шифрованием последовательных значений счетчика"""
+    result = []
+    counter = iv
+    for block in blocks:
+        result.append(vec_xor(block, cipher(counter, False)[:len(block)]))
+        counter = increment_counter(counter)
+    return result
+
+# Режимы работы: название -> (функция режима, нужно ли дополнение, нужен ли IV)
+MODES = {
+    "ECB": (mode_ecb, True, False),
+    "CBC": (mode_cbc, True, True),
+    "CFB": (mode_cfb, False, True),
+    "OFB": (mode_ofb, False, True),
+    "CTR": (mode_ctr, False, True),
+}
+
+def apply_mode(data, cipher, decrypt, block_size, mode="ECB", iv=None):
+    """
+    Шифрует или дешифрует сообщение блочным шифром в заданном режиме работы.
+    
+    В режимах ECB и CBC длина сообщения должна быть кратна размеру блока.
+    Режимы CFB, OFB и CTR работают как поточные: последний блок может быть
+    неполным, а блочный шифр всегда используется в направлении шифрования.
+    
+    Args:
+        data: Сообщение (список байтов)
+        cipher: Функция cipher(block, decrypt), преобразующая один блок
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        block_size: Размер блока в битах
+        mode: Название режима из MODES
+        iv: Вектор инициализации (для CTR - начальное значение счетчика)
+    
+    Returns:
+        Зашифрованное или дешифрованное сообщение
+    """
+    mode_func, padded, needs_iv = MODES[mode]
+    size = block_bytes(block_size)
+    
+    if needs_iv and (iv is None or len(iv) != size):
+        raise ValueError(f"Режиму {mode} нужен вектор инициализации длиной {size} байт")
+    
+    if padded:
+        blocks = split_blocks(data, block_size)
+    else:
+        blocks = [data[i:i + size] for i in range(0, len(data), size)]
+    
+    result = []
+    for block in mode_func(blocks, cipher, iv, decrypt):
+        result += block
+    return result
+
+def crypt_message(data, key, decrypt, rounds, block_size, mode="ECB", iv=None, 
+                  round_func=f, schedule=schedule_permute, split=None, 
+                  structure=None, branches=2, ip=None, fp=None, whitening=None, tweak=None):
+    """
+    Шифрует или дешифрует сообщение, разбивая его на блоки фиксированного размера.
+    
+    Args:
+        data: Сообщение (список байтов)
+        key: Ключ шифрования
+        decrypt: Флаг режима (True для дешифрования, False для шифрования)
+        rounds: Количество раундов шифрования
+        block_size: Размер блока в битах
+        mode: Режим работы из MODES
+        iv: Вектор инициализации
+        round_func: Функция раунда F (см. ROUND_FUNCTIONS)
+        schedule: Алгоритм расписания ключей (см. KEY_SCHEDULES)
+        split: Размер левой части блока в байтах (несбалансированная сеть)
+        structure: Обобщенная сеть из GFN_TYPES, LAI_MASSEY или None (классическая сеть)
+        branches: Число ветвей обобщенной сети
+        ip: Таблица начальной перестановки битов классической сети
+        fp: Таблица конечной перестановки битов (по умолчанию обратная к ip)
+        whitening: Пара ключей отбеливания (K_pre, K_post) классической сети
+        tweak: Твик, подмешиваемый в ключи раундов (см. tweak_keys)
+    
+    Returns:
+        Зашифрованное или дешифрованное сообщение
+    """
+    # Каждый блок проходит все раунды сети Фейстеля
+    def cipher(block, block_decrypt):
+        if structure in GFN_TYPES:
+            return crypt_block_gfn(block, key.copy(), block_decrypt, rounds, branches, 
+                                   structure, round_func, schedule, tweak=tweak)
+        if structure == LAI_MASSEY:
+            return crypt_block_lai_massey(block, key.copy(), block_decrypt, rounds, 
+                                          round_func, schedule, tweak=tweak)
+        return crypt_block(block, key.copy(), block_decrypt, rounds, round_func, schedule, split, 
+                           ip=ip, fp=fp, whitening=whitening, tweak=tweak)
+    
+    return apply_mode(data, cipher, decrypt, block_size, mode, iv)
+
+def gost_pad(data, size, procedure):
+    """
+    Дополняет сообщение до длины, кратной size байтам, по ГОСТ Р 34.13-2015.
+    
+    Процедура 1 дописывает нули, только если длина не кратна блоку;
+    процедура 2 всегда дописывает единичный бит и нули (байт 0x80
+    и нулевые байты); процедура 3 - как процедура 2, но только
+    для сообщения с неполным последним блоком.
+    
+    Args:
+        data: Сообщение (список байтов)
+        size: Размер блока в байтах
+        procedure: Номер процедуры дополнения (1, 2 или 3)
+    
+    Returns:
+        Дополненное сообщение
+    """
+    if procedure == 1:
+        return data + [0] * (-len(data) % size)
+    if procedure == 2:
+        return data + [0x80] + [0] * (-(len(data) + 1) % size)
+    if procedure == 3:
+        return data if data and len(data) % size == 0 else gost_pad(data, size, 2)
+    raise ValueError(f"Процедуры дополнения {procedure} нет в ГОСТ Р 34.13-2015")
+
+def gost_unpad(data, size, procedure):
+    """
+    Удаляет дополнение после расшифрования. Однозначно удаляется только
+    дополнение процедуры 2; после процедур 1 и 3 длину сообщения должен
+    знать получатель, поэтому данные возвращаются как есть.
+    """
+    if procedure == 2:
+        return unpad_iso7816(data, size)
+    return data
+
+# Процедуры дополнения ГОСТ Р 34.13-2015: название -> номер процедуры
+GOST_PADDINGS = {
+    "Процедура 1 (нули)": 1,
+    "Процедура 2 (единица и нули)": 2,
+    "Процедура 3 (как 2 для неполного блока)": 3,
+}
+
+def check_gost_param(name, value, size, low, high=None):
+    """
+    Проверяет параметр s или m режима ГОСТ Р 34.13-2015 (в битах).
+    
+    Args:
+        name: Название параметра для сообщения об ошибке
+        value: Значение параметра в битах
+        size: Размер блока в байтах
+        low: Наименьшее допустимое значение в битах
+        high: Наибольшее допустимое значение в битах или None
+    """
+    if value % 8 != 0:
+        raise ValueError(f"Параметр {name} задается в битах кратно 8, а не {value}")
+    if value < low or (high is not None and value > high):
+        limit = f"от {low} до {high}" if high is not None else f"не меньше {low}"
+        raise ValueError(f"Параметр {name} должен быть {limit} бит при n = {8 * size}, а не {value}")
+
+def check_gost_iv(iv, length, mode):
+    """Проверяет длину вектора инициализации режима ГОСТ Р 34.13-2015 (в байтах)"""
+    if iv is None or len(iv) != length:
+        raise ValueError(f"Режиму {mode} нужен вектор инициализации длиной {length} байт")
+
+def gost_ecb(data, cipher, decrypt, size, s=None, m=None, iv=None):
+    """Режим простой замены: каждый блок зашифровывается независимо"""
+    return sum(mode_ecb(split_blocks(data, 8 * size), cipher, iv, decrypt), [])
+
+def gost_ctr(data, cipher, decrypt, size, s, m=None, iv=None):
+    """
+    Режим гаммирования: CTR1 = IV || 0...0 (IV длиной n/2), гамма -
+    старшие s битов E(CTR_i), счетчик увеличивается по модулю 2^n.
+    """
+    check_gost_param("s", s, size, 8, 8 * size)
+    check_gost_iv(iv, size // 2, "CTR")
+    counter = iv + [0] * (size - size // 2)
+    result = []
+    for i in range(0, len(data), s // 8):
+        piece = data[i:i + s // 8]
+        result += vec_xor(piece, cipher(counter.copy(), False)[:len(piece)])
+        counter = increment_counter(counter)
+    return result
+
+def gost_ofb(data, cipher, decrypt, size, s, m, iv):
+    """
+    Режим гаммирования с обратной связью по выходу: регистр R длиной m = z*n,
+    гамма - старшие s битов Y = E(MSB_n(R)), затем R = LSB_(m-n)(R) || Y.
+    """
+    check_gost_param("s", s, size, 8, 8 * size)
+    check_gost_param("m", m, size, 8 * size)
+    if m % (8 * size) != 0:
+        raise ValueError(f"Параметр m режима OFB должен быть кратен n = {8 * size}, а не {m}")
+    check_gost_iv(iv, m // 8, "OFB")
+    register = iv.copy()
+    result = []
+    for i in range(0, len(data), s // 8):
+        piece = data[i:i + s // 8]
+        y = cipher(register[:size], False)
+        result += vec_xor(piece, y[:len(piece)])
+        register = register[size:] + y
+    return result
+
+def gost_cbc(data, cipher, decrypt, size, s=None, m=None, iv=None):
+    """
+    Режим простой замены с зацеплением: регистр R длиной m = z*n,
+    C_i = E(P_i ⊕ MSB_n(R)), затем R = LSB_(m-n)(R) || C_i.
+    """
+    check_gost_param("m", m, size, 8 * size)
+    if m % (8 * size) != 0:
+        raise ValueError(f"Параметр m режима CBC должен быть кратен n = {8 * size}, а не {m}")
+    check_gost_iv(iv, m // 8, "CBC")
+    register = iv.copy()
+    result = []
+    for block in split_blocks(data, 8 * size):
+        if decrypt:
+            result += vec_xor(cipher(block.copy(), True), register[:size])
+            register = register[size:] + block
+        else:
+            encrypted = cipher(vec_xor(block, register[:size]), False)
+            result += encrypted
+            register = register[size:] + encrypted
+    return result
+
+def gost_cfb(data, cipher, decrypt, size, s, m, iv):
+    """
+    Режим гаммирования с обратной связью по шифртексту: регистр R длиной m >= n,
+    гамма - старшие s битов E(MSB_n(R)), затем R = LSB_(m-s)(R) || C_i.
+    """
+    check_gost_param("s", s, size, 8, 8 * size)
+    check_gost_param("m", m, size, 8 * size)
+    check_gost_iv(iv, m // 8, "CFB")
+    register = iv.copy()
+    result = []
+    for i in range(0, len(data), s // 8):
+        piece = data[i:i + s // 8]
+        out = vec_xor(piece, cipher(register[:size], False)[:len(piece)])
+        result += out
+        register = register[s // 8:] + (piece if decrypt else out)
+    return result
+
+# Режимы ГОСТ Р 34.13-2015: название -> (функция режима, нужно ли дополнение,
+# используется ли параметр s, используется ли параметр m)
+GOST_MODES = {
+    "Простая замена (ECB)": (gost_ecb, True, False, False),
+    "Гаммирование (CTR)": (gost_ctr, False, True, False),
+    "Гаммирование с обратной связью по выходу (OFB)": (gost_ofb, False, True, True),
+    "Простая замена с зацеплением (CBC)": (gost_cbc, True, False, True),
+    "Гаммирование с обратной связью по шифртексту (CFB)": (gost_cfb, False, True, True),
+}
+
+# Константы B_n выработки вспомогательных ключей имитовставки (по размеру блока в байтах)
+GOST_MAC_CONSTANTS = {8: 0x1b, 16: 0x87}
+
+def gost_mac_keys(cipher, size):
+    """
+    Вырабатывает вспомогательные ключи имитовставки: R = E(0^n),
+    K1 = R << 1 (с XOR B_n, если старший бит R равен 1), K2 - так же из K1.
+    
+    Returns:
+        Пара ключей (K1, K2)
+    """
+    if size not in GOST_MAC_CONSTANTS:
+        raise ValueError(f"Имитовставка определена для блоков 64 и 128 бит, а не {8 * size}")
+    
+    def shift(vect):
+        value = vec_to_int(vect) << 1
+        if value >> (8 * size):
+            value ^= GOST_MAC_CONSTANTS[size]
+        return int_to_vec(value % (1 << (8 * size)), size)
+    
+    k1 = shift(cipher([0] * size, False))
+    return k1, shift(k1)
+
+def gost_mac(data, cipher, size, s):
+    """
+    Вырабатывает имитовставку по ГОСТ Р 34.13-2015: сообщение дополняется
+    процедурой 3 и зашифровывается в режиме простой замены с зацеплением
+    с нулевым IV; перед последним блоком к нему прибавляется K1 (полный
+    блок) или K2 (блок дополнен), а имитовставка - старшие s битов результата.
+    
+    Args:
+        data: Сообщение (список байтов)
+        cipher: Функция cipher(block, decrypt), преобразующая один блок
+        size: Размер блока в байтах
+        s: Длина имитовставки в битах
+    
+    Returns:
+        Имитовставка (список байтов)
+    """
+    check_gost_param("s", s, size, 8, 8 * size)
+    k1, k2 = gost_mac_keys(cipher, size)
+    key = k1 if data and len(data) % size == 0 else k2
+    blocks = split_blocks(gost_pad(data, size, 3), 8 * size)
+    chain = [0] * size
+    for block in blocks[:-1]:
+        chain = cipher(vec_xor(block, chain), False)
+    return cipher(vec_xor(vec_xor(blocks[-1], chain), key), False)[:s // 8]
+
+# Примеры ГОСТ Р 34.13-2015 для шифра "Магма" (приложение А.2):
+# ключ, открытый текст и для каждого режима параметры s, m, IV и шифртекст
+GOST_MODE_EXAMPLE_KEY = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"
+GOST_MODE_EXAMPLE_TEXT = "92def06b3c130a59db54c704f8189d204a98fb2e67a8024c8912409b17b57e41"
+GOST_MODE_EXAMPLES = {
+    "Простая замена (ECB)": (64, 64, "", 
+        "2b073f0494f372a0de70e715d3556e4811d8d9e9eacfbc1e7c68260996c67efb"),
+    "Гаммирование (CTR)": (64, 64, "12345678", 
+        "4e98110c97b7b93c3e250d93d6e85d69136d868807b2dbef568eb680ab52a12d"),
+    "Гаммирование с обратной связью по выходу (OFB)": (64, 128, "1234567890abcdef234567890abcdef1", 
+        "db37e0e266903c830d46644c1f9a089ca0f83062430e327ec824efb8bd4fdb05"),
+    "Простая замена с зацеплением (CBC)": (64, 192, 
+        "1234567890abcdef234567890abcdef134567890abcdef12", 
+        "96d1b05eea683919aff76129abb937b95058b4a1c4bc001920b78b1a7cd7e667"),
+    "Гаммирование с обратной связью по шифртексту (CFB)": (64, 128, "1234567890abcdef234567890abcdef1", 
+        "db37e0e266903c830d46644c1f9a089c24bdd2035315d38bbcc0321421075505"),
+}
+# Имитовставка примера: длина s в битах и значение
+GOST_MAC_EXAMPLE = (32, "154e7210")
+
+def run_gost_mode_examples(crypt):
+    """
+    Проверяет режимы и имитовставку по примерам ГОСТ Р 34.13-2015:
+    зашифрование должно дать шифртекст стандарта, а расшифрование - исходный текст.
+    
+    Args:
+        crypt: Функция шифра "Магма" (block, key, decrypt) -> block
+    
+    Returns:
+        Список строк с результатами проверки
+    """
+    key = list(bytes.fromhex(GOST_MODE_EXAMPLE_KEY))
+    plain = list(bytes.fromhex(GOST_MODE_EXAMPLE_TEXT))
+    
+    def cipher(block, decrypt):
+        return crypt(block, key.copy(), decrypt)
+    
+    lines = []
+    for name, (s, m, iv, expected) in GOST_MODE_EXAMPLES.items():
+        mode_func = GOST_MODES[name][0]
+        iv = list(bytes.fromhex(iv))
+        result = bytes(mode_func(plain.copy(), cipher, False, 8, s, m, iv)).hex()
+        back = mode_func(list(bytes.fromhex(result)), cipher, True, 8, s, m, iv)
+        status = "OK" if result == expected and back == plain else "ОШИБКА"
+        lines.append(f"{name}, s = {s}, m = {m}: {result} {status}")
+    s, expected = GOST_MAC_EXAMPLE
+    result = bytes(gost_mac(plain, cipher, 8, s)).hex()
+    status = "OK" if result == expected else "ОШИБКА"
+    lines.append(f"Имитовставка, s = {s}: {result} (ожидается {expected}) {status}")
+    return lines
+
+class FeistelBlockItem:
+    """Класс для визуализации блока данных в сети Фейстеля"""
+    
+    def __init__(self, scene, x, y, width, height, left_data, right_data, title="", pad_len=0):
+        self.scene = scene
+        self.x = x
+        self.y = y
+        self.width = width
+        self.height = height
+        self.left_data = left_data
+        self.right_data = right_data
+        self.title = title
+        self.pad_len = pad_len  # Число байтов дополнения в конце блока
+        
+        self.draw()
+        
+    def draw(self):
+        # Рисуем общую рамку
+        self.scene.addRect(self.x, self.y, self.width, self.height, 
+                           QPen(Qt.GlobalColor.black), QBrush(Qt.GlobalColor.white))
+        
+        # Рисуем разделение на левую и правую части пропорционально их размеру
+        total = len(self.left_data) + len(self.right_data)
+        divider_x = self.x + (self.width * len(self.left_data) / total if total else self.width / 2)
+        self.scene.addLine(divider_x, self.y, 
+                           divider_x, self.y + self.height, 
+                           QPen(Qt.GlobalColor.black))
+        
+        # Добавляем заголовок
+        if self.title:
+            title_item = self.scene.addText(self.title, QFont("Arial", 10))
+            title_item.setPos(self.x + self.width/2 - title_item.boundingRect().width()/2, 
+                             self.y - 20)
+        
+        # Отделяем байты дополнения от полезных данных
+        payload_len = len(self.left_data) + len(self.right_data) - self.pad_len
+        left_data = self.left_data[:payload_len]
+        right_data = self.right_data[:max(0, payload_len - len(self.left_data))]
+        
+        # Отображаем данные левой и правой частей
+        left_text = self.format_data(left_data)
+        right_text = self.format_data(right_data)
+        
+        left_item = self.scene.addText(left_text, QFont("Courier", 8))
+        right_item = self.scene.addText(right_text, QFont("Courier", 8))
+        
+        left_item.setPos(self.x + 5, self.y + 5)
+        right_item.setPos(divider_x + 5, self.y + 5)
+        
+        # Байты дополнения показываем отдельной полосой под блоком
+        if self.pad_len:
+            padding = (self.left_data + self.right_data)[payload_len:]
+            self.scene.addRect(self.x, self.y + self.height, self.width, 20, 
+                               QPen(Qt.GlobalColor.black), QBrush(QColor(255, 240, 200)))
+            pad_text = "Дополнение: " + ' '.join([f'{b:02x}' for b in padding])
+            pad_item = self.scene.addText(pad_text, QFont("Courier", 8))
+            pad_item.setPos(self.x + 5, self.y + self.height)
     
     def format_data(self, data):
         """Форматирует данные для отображения"""
@@ -205,10 +2966,210 @@ class FeistelBlockItem:
                 return str(data)
         return str(data)
 
+class BranchBlockItem:
+    """Класс для визуализации блока обобщенной сети Фейстеля: по дорожке на ветвь"""
+    
+    def __init__(self, scene, x, y, width, height, branches, title=""):
+        self.scene = scene
+        self.x = x
+        self.y = y
+        self.width = width
+        self.height = height
+        self.branches = branches
+        self.title = title
+        
+        self.draw()
+    
+    def lane_y(self, i):
+        """Возвращает вертикальную координату центра дорожки ветви i"""
+        return self.y + self.height * (i + 0.5) / len(self.branches)
+    
+    def draw(self):
+        # Рисуем общую рамку
+        self.scene.addRect(self.x, self.y, self.width, self.height, 
+                           QPen(Qt.GlobalColor.black), QBrush(Qt.GlobalColor.white))
+        
+        # Добавляем заголовок
+        if self.title:
+            title_item = self.scene.addText(self.title, QFont("Arial", 10))
+            title_item.setPos(self.x + self.width/2 - title_item.boundingRect().width()/2, 
+                             self.y - 20)
+        
+        # Каждая ветвь рисуется отдельной горизонтальной дорожкой
+        lane_height = self.height / len(self.branches)
+        for i, branch in enumerate(self.branches):
+            if i > 0:
+                self.scene.addLine(self.x, self.y + i * lane_height, 
+                                   self.x + self.width, self.y + i * lane_height, 
+                                   QPen(Qt.GlobalColor.gray))
+            text = f"X{i}: " + ' '.join([f'{b:02x}' for b in branch])
+            item = self.scene.addText(text, QFont("Courier", 8))
+            item.setPos(self.x + 5, self.y + i * lane_height)
+
+class PermutationWiringItem:
+    """Класс для визуализации перестановки битов: соединения входов с выходами"""
+    
+    # Цвета соединений по номеру входного байта
+    COLORS = [QColor(200, 0, 0), QColor(0, 120, 0), QColor(0, 0, 200), QColor(180, 120, 0), 
+              QColor(150, 0, 150), QColor(0, 140, 140), QColor(90, 90, 90), QColor(230, 90, 0)]
+    
+    def __init__(self, scene, x, y, width, height, table):
+        self.scene = scene
+        self.x = x
+        self.y = y
+        self.width = width
+        self.height = height
+        self.table = table  # Номера битов входа (с единицы) для каждого бита выхода
+        
+        self.draw()
+    
+    def bit_x(self, i):
+        """Возвращает горизонтальную координату бита i (с нуля)"""
+        return self.x + self.width * (i + 0.5) / len(self.table)
+    
+    def draw(self):
+        # Границы байтов на входе (сверху) и выходе (снизу)
+        for i in range(0, len(self.table) + 1, 8):
+            x = self.x + self.width * i / len(self.table)
+            self.scene.addLine(x, self.y - 5, x, self.y + 3, QPen(Qt.GlobalColor.black))
+            self.scene.addLine(x, self.y + self.height - 3, x, self.y + self.height + 5, 
+                               QPen(Qt.GlobalColor.black))
+        
+        # Бит выхода i берется из бита входа table[i]
+        for i, source in enumerate(self.table):
+            pen = QPen(self.COLORS[(source - 1) // 8 % len(self.COLORS)], 1)
+            self.scene.addLine(self.bit_x(source - 1), self.y, 
+                               self.bit_x(i), self.y + self.height, pen)
+
+class PermutationVisualizer:
+    """Класс для визуализации начальной или конечной перестановки битов блока"""
+    
+    def __init__(self, scene, x, y, width, height, title, prev_state, curr_state, table):
+        self.scene = scene
+        self.x = x
+        self.y = y
+        self.width = width
+        self.height = height
+        self.title = title
+        self.prev_state = prev_state
+        self.curr_state = curr_state
+        self.table = table
+        
+        self.draw()
+    
+    def draw(self):
+        # Рисуем заголовок этапа
+        title_item = self.scene.addText(self.title, QFont("Arial", 12, QFont.Weight.Bold))
+        title_item.setPos(self.x + 10, self.y + 10)
+        
+        # Блок до перестановки сверху, после - снизу
+        before = "До:    " + ' '.join([f'{b:02x}' for b in self.prev_state])
+        after = "После: " + ' '.join([f'{b:02x}' for b in self.curr_state])
+        before_item = self.scene.addText(before, QFont("Courier", 9))
+        before_item.setPos(self.x + 50, self.y + 40)
+        after_item = self.scene.addText(after, QFont("Courier", 9))
+        after_item.setPos(self.x + 50, self.y + self.height - 30)
+        
+        PermutationWiringItem(self.scene, self.x + 50, self.y + 70, 
+                              self.width - 100, self.height - 110, self.table)
+
+class WhiteningVisualizer:
+    """Класс для визуализации отбеливания: XOR блока с ключом отбеливания"""
+    
+    def __init__(self, scene, x, y, width, height, title, prev_state, curr_state, whitening_key):
+        self.scene = scene
+        self.x = x
+        self.y = y
+        self.width = width
+        self.height = height
+        self.title = title
+        self.prev_state = prev_state
+        self.curr_state = curr_state
+        self.whitening_key = whitening_key
+        
+        self.draw()
+    
+    def draw(self):
+        # Рисуем заголовок этапа
+        title_item = self.scene.addText(self.title, QFont("Arial", 12, QFont.Weight.Bold))
+        title_item.setPos(self.x + 10, self.y + 10)
+        
+        # Блок, ключ и результат - столбиком, как сложение по XOR
+        def hex_vec(v):
+            return ' '.join([f'{b:02x}' for b in v])
+        
+        lines = [
+            ("Блок:      ", hex_vec(self.prev_state)),
+            ("⊕ Ключ:    ", hex_vec(self.whitening_key)),
+            ("Результат: ", hex_vec(self.curr_state)),
+        ]
+        for i, (label, value) in enumerate(lines):
+            item = self.scene.addText(label + value, QFont("Courier", 10))
+            if i == 1:
+                item.setDefaultTextColor(QColor(200, 0, 0))
+            item.setPos(self.x + 50, self.y + 50 + 40 * i)
+        
+        # Черта под слагаемыми
+        line_y = self.y + 50 + 40 * 2 - 5
+        self.scene.addLine(self.x + 50, line_y, self.x + self.width - 50, line_y, 
+                           QPen(Qt.GlobalColor.black))
+
+class KeySetupVisualizer:
+    """Класс для визуализации расписания ключей, меняющего P-массив и S-блоки (Blowfish)"""
+    
+    ROW_HEIGHT = 150  # Высота строки одного снимка
+    
+    def __init__(self, scene, x, y, width, steps):
+        self.scene = scene
+        self.x = x
+        self.y = y
+        self.width = width
+        self.steps = steps  # Снимки (этап, P-массив, S-блоки), см. blowfish_key_setup
+        self.height = 50 + len(steps) * self.ROW_HEIGHT
+        
+        self.draw()
+    
+    def draw(self):
+        # Рисуем заголовок этапа
+        title = "Расписание ключей: P-массив и S-блоки (цветом выделены слова, замененные на этапе)"
+        title_item = self.scene.addText(title, QFont("Arial", 12, QFont.Weight.Bold))
+        title_item.setPos(self.x + 10, self.y + 10)
+        
+        prev_p, prev_s = None, None
+        for row, (name, p_array, sboxes) in enumerate(self.steps):
+            y = self.y + 50 + row * self.ROW_HEIGHT
+            changed_p = [prev_p is None or a != b for a, b in zip(p_array, prev_p or p_array)]
+            changed_s = [[prev_s is None or a != b for a, b in zip(box, prev_box)] 
+                         for box, prev_box in zip(sboxes, prev_s or sboxes)]
+            count = sum(changed_p) + sum(map(sum, changed_s))
+            label = self.scene.addText(f"{name} (заменено слов: {count})", QFont("Arial", 10))
+            label.setPos(self.x + 50, y)
+            
+            # P-массив - словами в HEX, замененные слова красным
+            for i, word in enumerate(p_array):
+                item = self.scene.addText(f"{word:08x}", QFont("Courier", 8))
+                if changed_p[i] and prev_p is not None:
+                    item.setDefaultTextColor(QColor(200, 0, 0))
+                item.setPos(self.x + 50 + (i % 9) * 75, y + 22 + (i // 9) * 16)
+            
+            # S-блоки - полосами по 256 клеток; цвет клетки задается значением слова
+            cell = (self.width - 100) / 256
+            for b, box in enumerate(sboxes):
+                strip_y = y + 60 + b * 18
+                for i, word in enumerate(box):
+                    if changed_s[b][i]:
+                        color = QColor.fromHsv((word >> 16) % 360, 200, 230)
+                    else:
+                        color = QColor(225, 225, 225)
+                    self.scene.addRect(self.x + 50 + i * cell, strip_y, cell, 14, 
+                                       QPen(Qt.PenStyle.NoPen), QBrush(color))
+            prev_p, prev_s = p_array, sboxes
+
 class FeistelRoundVisualizer:
     """Класс для визуализации одного раунда сети Фейстеля"""
     
-    def __init__(self, scene, x, y, width, height, round_num, prev_state, curr_state, round_key):
+    def __init__(self, scene, x, y, width, height, round_num, prev_state, curr_state, round_key, 
+                 split=None, wiring=None, round_func=None, inverse=False, label=None):
         self.scene = scene
         self.x = x
         self.y = y
@@ -218,12 +3179,19 @@ class FeistelRoundVisualizer:
         self.prev_state = prev_state
         self.curr_state = curr_state
         self.round_key = round_key
+        self.split = split if split is not None else len(prev_state) // 2  # Размер левой части
+        self.wiring = wiring  # Соединения ветвей обобщенной сети (см. gfn_wiring)
+        self.round_func = round_func  # Функция F этого раунда (для побитового вида сдвига)
+        self.inverse = inverse  # Раунд обращен (дешифрование несбалансированной сети)
+        self.label = label  # Обозначение раунда в стандарте шифра, например G[K1](a1, a0)
         
         self.draw()
     
     def draw(self):
         # Рисуем заголовок раунда
         title = f"Раунд {self.round_num}"
+        if self.label:
+            title += f": {self.label}"
         title_item = self.scene.addText(title, QFont("Arial", 12, QFont.Weight.Bold))
         title_item.setPos(self.x + 10, self.y + 10)
         
@@ -231,24 +3199,28 @@ class FeistelRoundVisualizer:
         block_width = 200
         block_height = 80
         
+        if self.wiring:
+            self.draw_branches(block_width)
+            return
+        
         # Отображаем входное состояние (до раунда)
-        left_prev = self.prev_state[:len(self.prev_state)//2]
-        right_prev = self.prev_state[len(self.prev_state)//2:]
+        left_prev = self.prev_state[:self.split]
+        right_prev = self.prev_state[self.split:]
         
         FeistelBlockItem(self.scene, self.x + 50, self.y + 40, 
                         block_width, block_height, 
                         left_prev, right_prev, "До раунда")
         
         # Отображаем выходное состояние (после раунда)
-        left_curr = self.curr_state[:len(self.curr_state)//2]
-        right_curr = self.curr_state[len(self.curr_state)//2:]
+        left_curr = self.curr_state[:self.split]
+        right_curr = self.curr_state[self.split:]
         
         FeistelBlockItem(self.scene, self.x + self.width - block_width - 50, 
                         self.y + 40, block_width, block_height, 
                         left_curr, right_curr, "После раунда")
         
         # Отображаем ключ раунда
-        key_text = f"Ключ раунда: {bytes(self.round_key).decode('utf-8', errors='replace')}"
+        key_text = f"Ключ раунда: {' '.join([f'{b:02x}' for b in self.round_key])}"
         key_item = self.scene.addText(key_text, QFont("Courier", 9))
         key_item.setPos(self.x + self.width/2 - key_item.boundingRect().width()/2, 
                        self.y + self.height - 30)
@@ -268,6 +3240,146 @@ class FeistelRoundVisualizer:
         self.scene.addLine(arrow_x, arrow_y, 
                           arrow_x - arrow_size, arrow_y + arrow_size, 
                           QPen(Qt.GlobalColor.black, 2))
+        
+        pbox_y = self.y + 40 + block_height + 10
+        shift = rotation_shift(self.round_func)
+        if shift is not None:
+            self.draw_rotation(shift, pbox_y)
+            pbox_y += 40
+        
+        table = pbox_table(self.round_func)
+        if table is not None:
+            label = self.scene.addText("P-блок на выходе F:", QFont("Arial", 9))
+            label.setPos(self.x + 50, pbox_y)
+            PermutationWiringItem(self.scene, self.x + 50, pbox_y + 25, self.width - 100, 
+                                  self.y + self.height - 40 - pbox_y - 25, table)
+    
+    def draw_rotation(self, shift, y):
+        """Показывает побитово циклический сдвиг всей половины блока внутри F"""
+        # Вход F - правая часть блока до раунда (при обращении - после)
+        source = (self.curr_state if self.inverse else self.prev_state)[self.split:]
+        before = vec_invert(vec_xor(source, self.round_key))
+        after = vec_rotl(before, shift)
+        
+        def bits(v):
+            return ' '.join([f'{b:08b}' for b in v])
+        
+        direction = f"<<< {shift}" if shift >= 0 else f">>> {-shift}"
+        lines = [
+            f"F: ¬(R ⊕ K) = {bits(before)}",
+            f"   {direction:<9} = {bits(after)}",
+        ]
+        for i, line in enumerate(lines):
+            item = self.scene.addText(line, QFont("Courier", 8))
+            item.setPos(self.x + 50, y + 18 * i)
+    
+    def draw_branches(self, block_width):
+        """Рисует раунд обобщенной сети: дорожки ветвей и их соединения"""
+        k = len(self.wiring)
+        width = len(self.prev_state) // k
+        block_height = max(80, 22 * k)
+        
+        prev_branches = [self.prev_state[i * width:(i + 1) * width] for i in range(k)]
+        curr_branches = [self.curr_state[i * width:(i + 1) * width] for i in range(k)]
+        
+        prev_item = BranchBlockItem(self.scene, self.x + 50, self.y + 40, 
+                                    block_width, block_height, prev_branches, "До раунда")
+        curr_x = self.x + self.width - block_width - 50
+        curr_item = BranchBlockItem(self.scene, curr_x, self.y + 40, 
+                                    block_width, block_height, curr_branches, "После раунда")
+        
+        # Соединяем дорожки: ветви, к которым добавляется F, выделяем цветом
+        for src, dst, with_f in self.wiring:
+            pen = QPen(QColor(200, 0, 0) if with_f else Qt.GlobalColor.black, 2)
+            self.scene.addLine(self.x + 50 + block_width, prev_item.lane_y(src), 
+                               curr_x, curr_item.lane_y(dst), pen)
+            if with_f:
+                f_item = self.scene.addText("⊕F", QFont("Arial", 9, QFont.Weight.Bold))
+                f_item.setDefaultTextColor(QColor(200, 0, 0))
+                f_item.setPos(curr_x - 30, curr_item.lane_y(dst) - 18)
+        
+        # Отображаем ключи раунда
+        key_text = f"Ключи раунда: {' '.join([f'{b:02x}' for b in self.round_key])}"
+        key_item = self.scene.addText(key_text, QFont("Courier", 9))
+        key_item.setPos(self.x + self.width/2 - key_item.boundingRect().width()/2, 
+                       self.y + self.height - 30)
+
+class LaiMasseyRoundVisualizer:
+    """Класс для визуализации одного раунда схемы Лая-Мэсси"""
+    
+    def __init__(self, scene, x, y, width, height, round_num, prev_state, curr_state, round_key, 
+                 round_func=f, decrypt=False, last=False):
+        self.scene = scene
+        self.x = x
+        self.y = y
+        self.width = width
+        self.height = height
+        self.round_num = round_num
+        self.prev_state = prev_state
+        self.curr_state = curr_state
+        self.round_key = round_key
+        self.round_func = round_func
+        self.decrypt = decrypt
+        self.last = last  # Последний раунд шифрования выполняется без σ
+        
+        self.draw()
+    
+    def draw(self):
+        # Рисуем заголовок раунда
+        title = f"Раунд {self.round_num} (Лай-Мэсси)"
+        title_item = self.scene.addText(title, QFont("Arial", 12, QFont.Weight.Bold))
+        title_item.setPos(self.x + 10, self.y + 10)
+        
+        block_width = 200
+        block_height = 80
+        half = len(self.prev_state) // 2
+        
+        FeistelBlockItem(self.scene, self.x + 50, self.y + 40, block_width, block_height, 
+                        self.prev_state[:half], self.prev_state[half:], "До раунда")
+        FeistelBlockItem(self.scene, self.x + self.width - block_width - 50, self.y + 40, 
+                        block_width, block_height, 
+                        self.curr_state[:half], self.curr_state[half:], "После раунда")
+        
+        # Промежуточные значения считаем по входу раунда шифрования:
+        # при дешифровании это состояние после обращенного раунда
+        source = self.curr_state if self.decrypt else self.prev_state
+        d = vec_xor(source[:half], source[half:])
+        t = self.round_func(d, self.round_key)
+        
+        def hex_vec(v):
+            return ' '.join([f'{b:02x}' for b in v])
+        
+        sigma = "L' = L ⊕ T (без σ)" if self.last else "L' = σ(L ⊕ T)"
+        if self.decrypt:
+            sigma = "L ⊕ T = L'" if self.last else "L ⊕ T = σ⁻¹(L')"
+        lines = [
+            f"D = L ⊕ R = {hex_vec(d)}",
+            f"T = F(D, K) = {hex_vec(t)}",
+            f"{sigma},  R' = R ⊕ T",
+        ]
+        
+        # Центральная колонка: вычисление разности, функции F и ортоморфизма
+        center_x = self.x + 50 + block_width + 20
+        center_width = self.width - 2 * (block_width + 70)
+        self.scene.addRect(center_x, self.y + 40, center_width, block_height, 
+                           QPen(Qt.GlobalColor.black), QBrush(QColor(235, 245, 255)))
+        for i, line in enumerate(lines):
+            item = self.scene.addText(line, QFont("Courier", 8))
+            item.setPos(center_x + 5, self.y + 45 + 22 * i)
+        
+        # Стрелки от входа к вычислениям и от вычислений к выходу
+        arrow_y = self.y + 40 + block_height/2
+        self.scene.addLine(self.x + 50 + block_width, arrow_y, center_x, arrow_y, 
+                          QPen(Qt.GlobalColor.black, 2))
+        self.scene.addLine(center_x + center_width, arrow_y, 
+                          self.x + self.width - 50 - block_width, arrow_y, 
+                          QPen(Qt.GlobalColor.black, 2))
+        
+        # Отображаем ключ раунда
+        key_text = f"Ключ раунда: {hex_vec(self.round_key)}"
+        key_item = self.scene.addText(key_text, QFont("Courier", 9))
+        key_item.setPos(self.x + self.width/2 - key_item.boundingRect().width()/2, 
+                       self.y + self.height - 30)
 
 class ZoomableGraphicsView(QGraphicsView):
     def __init__(self, scene):
@@ -331,90 +3443,226 @@ class ZoomableGraphicsView(QGraphicsView):
 class FeistelVisualizer:
     """Класс для визуализации всего процесса шифрования/дешифрования"""
     
-    def __init__(self, block, key, rounds, decrypt=False):
+    def __init__(self, block, key, rounds, decrypt=False, pad_len=0, round_func=f, 
+                 schedule=schedule_permute, split=None, structure=None, branches=2, 
+                 ip=None, fp=None, whitening=None, tweak=None, cipher=None, cascade=None):
         self.original_block = block.copy()
         self.key = key.copy()
         self.rounds = rounds
         self.decrypt = decrypt
+        self.pad_len = pad_len  # Дополнение в открытом тексте блока
+        self.round_func = round_func
+        self.schedule = schedule
+        self.split = split if split is not None else len(block) // 2  # Размер левой части
+        self.structure = structure  # Обобщенная сеть из GFN_TYPES, LAI_MASSEY или None
+        self.branches = branches
+        self.ip = ip  # Начальная и конечная перестановки битов
+        self.fp = fp
+        self.whitening = whitening  # Ключи отбеливания (K_pre, K_post)
+        self.tweak = tweak
+        self.cipher = cipher  # Стандартный шифр из CIPHERS или None
+        # Ключи k2[, k3] каскада EDE; ключ key становится k1
+        self.cascade = [k.copy() for k in cascade] if cascade else []
         
-        # Генерируем все промежуточные состояния
-        self.states = self.generate_states()
+        # Генерируем все промежуточные состояния каждого прохода
+        self.sections = self.generate_sections()
     
-    def generate_states(self):
-        """Генерирует список всех промежуточных состояний блока и ключей"""
-        states = []
-        keys = keys_gen(self.key.copy(), self.decrypt, self.rounds)
-        
-        current_block = self.original_block.copy()
-        states.append(("Начальный блок", current_block.copy(), None))
-        
-        for i, round_key in enumerate(keys):
-            current_block = crypt_round(current_block, round_key)
-            states.append((f"Раунд {i+1}", current_block.copy(), round_key))
-        
-        # Финальная перестановка
-        left = current_block[:len(current_block)//2]
-        right = current_block[len(current_block)//2:]
-        final_block = right + left
-        states.append(("Финальный результат", final_block, None))
+    def generate_sections(self):
+        """
+        Генерирует проходы шифра: один проход или три прохода каскада EDE.
         
+        Returns:
+            Список четверок (обозначение прохода или None, флаг дешифрования прохода,
+            ключ прохода, промежуточные состояния прохода)
+        """
+        if not self.cascade:
+            return [(None, self.decrypt, self.key, 
+                     self.generate_states(self.original_block, self.key, self.decrypt))]
+        keys = [self.key] + self.cascade
+        sections = []
+        block = self.original_block
+        for index, pass_decrypt in cascade_passes(len(keys), self.decrypt):
+            states = self.generate_states(block, keys[index], pass_decrypt)
+            sections.append((cascade_pass_name(index, pass_decrypt), pass_decrypt, keys[index], states))
+            block = states[-1][1]
+        return sections
+    
+    def generate_states(self, block, key, decrypt):
+        """Генерирует список всех промежуточных состояний блока и ключей одного прохода"""
+        states = []
+        if self.cipher is not None:
+            CIPHERS[self.cipher]["crypt"](block.copy(), key.copy(), decrypt, trace=states)
+        elif self.structure in GFN_TYPES:
+            crypt_block_gfn(block.copy(), key.copy(), decrypt, self.rounds, 
+                            self.branches, self.structure, self.round_func, self.schedule, 
+                            trace=states, tweak=self.tweak)
+        elif self.structure == LAI_MASSEY:
+            crypt_block_lai_massey(block.copy(), key.copy(), decrypt, 
+                                   self.rounds, self.round_func, self.schedule, trace=states, 
+                                   tweak=self.tweak)
+        else:
+            crypt_block(block.copy(), key.copy(), decrypt, self.rounds, 
+                        self.round_func, self.schedule, self.split, trace=states, 
+                        ip=self.ip, fp=self.fp, whitening=self.whitening, tweak=self.tweak)
         return states
     
     def visualize(self, scene):
         """Отображает визуализацию на графической сцене"""
         scene.clear()
         
+        width = 1000
+        x_margin = 50
+        y_margin = 50
+        y_offset = y_margin
+        
+        # Заголовок каскада: порядок проходов и рост длины ключа
+        if self.cascade:
+            keys = [self.key] + self.cascade
+            bits = [cipher_key_bits(k, self.cipher) for k in keys]
+            nominal, mitm = cascade_key_bits(bits)
+            order = " → ".join(name for name, _, _, _ in self.sections)
+            title = (f"Каскад EDE из {len(keys)} ключей: {order}\n"
+                     f"Длина ключа: один проход {bits[0]} бит, каскад {nominal} бит, "
+                     f"против встречи посередине {mitm} бит")
+            title_item = scene.addText(title, QFont("Arial", 16, QFont.Weight.Bold))
+            title_item.setPos(x_margin, y_offset)
+            y_offset += 80
+        
+        # Каждый проход выводится отдельным разделом. Дополнение видно во входе
+        # первого прохода при шифровании и в выходе последнего при дешифровании
+        for number, (name, decrypt, key, states) in enumerate(self.sections):
+            pad_in = self.pad_len if number == 0 and not self.decrypt else 0
+            pad_out = self.pad_len if number == len(self.sections) - 1 and self.decrypt else 0
+            y_offset = self.draw_pass(scene, y_offset, name, decrypt, key, states, pad_in, pad_out)
+        
+        # Устанавливаем размер сцены
+        scene.setSceneRect(0, 0, width + 2*x_margin, y_offset + y_margin)
+    
+    def draw_pass(self, scene, y_offset, name, decrypt, key, states, pad_in, pad_out):
+        """
+        Отображает один проход шифра: заголовок, исходный блок и результат,
+        перестановки, отбеливание и раунды.
+        
+        Args:
+            scene: Графическая сцена
+            y_offset: Вертикальная позиция начала раздела
+            name: Обозначение прохода каскада или None
+            decrypt: Флаг дешифрования прохода
+            key: Ключ прохода
+            states: Промежуточные состояния прохода
+            pad_in: Число байтов дополнения во входном блоке
+            pad_out: Число байтов дополнения в результате
+        
+        Returns:
+            Вертикальная позиция после раздела
+        """
         # Увеличиваем размеры для лучшей видимости
         width = 1000  # Было 800
         height = 300  # Было 200
         x_margin = 50
-        y_margin = 50
-        y_offset = y_margin
         
         # Заголовок
-        operation_type = "Дешифрование" if self.decrypt else "Шифрование"
+        operation_type = "Дешифрование" if decrypt else "Шифрование"
         title = f"{operation_type} с использованием сети Фейстеля ({self.rounds} раундов)"
+        if 2 * self.split != len(self.original_block):
+            right_size = len(self.original_block) - self.split
+            title += f"\nНесбалансированная сеть: левая часть {self.split} байт, правая {right_size} байт"
+        wiring = None
+        if self.structure in GFN_TYPES:
+            wiring = gfn_wiring(self.structure, self.branches, decrypt)
+            diffusion = gfn_diffusion_rounds(self.structure, self.branches)
+            title = (f"{operation_type}: обобщенная сеть Фейстеля {self.structure}, "
+                     f"{self.branches} ветвей ({self.rounds} раундов)\n"
+                     f"Полная диффузия ветвей за {diffusion} раундов")
+        elif self.structure == LAI_MASSEY:
+            title = f"{operation_type} по схеме Лая-Мэсси ({self.rounds} раундов)"
+        if self.cipher is not None:
+            title = f"{operation_type}: {self.cipher} ({self.rounds} раундов)"
+        if self.tweak:
+            title += f"\nТвик: {' '.join([f'{b:02x}' for b in self.tweak])} (подмешан в ключи раундов)"
+        if name is not None:
+            title = f"Проход {name}. {title}"
         title_item = scene.addText(title, QFont("Arial", 14, QFont.Weight.Bold))
         title_item.setPos(x_margin, y_offset)
         y_offset += 50
         
         # Отображаем начальное и конечное состояния
-        initial_block = self.states[0][1]
-        final_block = self.states[-1][1]
+        initial_block = states[0][1]
+        final_block = states[-1][1]
         
-        left_initial = initial_block[:len(initial_block)//2]
-        right_initial = initial_block[len(initial_block)//2:]
+        left_initial = initial_block[:self.split]
+        right_initial = initial_block[self.split:]
         
-        left_final = final_block[:len(final_block)//2]
-        right_final = final_block[len(final_block)//2:]
+        left_final = final_block[:self.split]
+        right_final = final_block[self.split:]
         
         block_width = 400  # Было 300
         block_height = 150  # Было 100
         
-        # Начальный блок
-        FeistelBlockItem(scene, x_margin, y_offset, block_width, block_height, 
-                        left_initial, right_initial, "Исходный блок")
-        
-        # Конечный блок
-        FeistelBlockItem(scene, x_margin + width - block_width, y_offset, 
-                        block_width, block_height, left_final, right_final, 
-                        "Результат")
+        if wiring:
+            branch_width = len(initial_block) // self.branches
+            BranchBlockItem(scene, x_margin, y_offset, block_width, block_height, 
+                            [initial_block[i * branch_width:(i + 1) * branch_width] 
+                             for i in range(self.branches)], "Исходный блок")
+            BranchBlockItem(scene, x_margin + width - block_width, y_offset, block_width, 
+                            block_height, [final_block[i * branch_width:(i + 1) * branch_width] 
+                                           for i in range(self.branches)], "Результат")
+        else:
+            # Начальный блок (при шифровании открытый текст содержит дополнение)
+            FeistelBlockItem(scene, x_margin, y_offset, block_width, block_height, 
+                            left_initial, right_initial, "Исходный блок", pad_in)
+            
+            # Конечный блок (при дешифровании дополнение появляется в результате)
+            FeistelBlockItem(scene, x_margin + width - block_width, y_offset, 
+                            block_width, block_height, left_final, right_final, 
+                            "Результат", pad_out)
         
         y_offset += block_height + 50
         
-        # Визуализируем каждый раунд
-        for i in range(1, len(self.states) - 1):
-            prev_state = self.states[i-1][1]
-            curr_state = self.states[i][1]
-            round_key = self.states[i][2]
+        # Расписание ключей, задающее S-блоки, показываем перед раундами
+        if self.cipher is not None and "key_setup" in CIPHERS[self.cipher]:
+            steps = []
+            CIPHERS[self.cipher]["key_setup"](key, steps)
+            setup = KeySetupVisualizer(scene, x_margin, y_offset, width, steps)
+            y_offset += setup.height + 30
+        
+        # Визуализируем каждый раунд, перестановки IP/FP и отбеливание вокруг раундов
+        round_num = 0
+        for i in range(1, len(states) - 1):
+            stage, curr_state, round_key = states[i]
+            prev_state = states[i-1][1]
+            
+            if stage in ("Начальная перестановка", "Конечная перестановка"):
+                PermutationVisualizer(scene, x_margin, y_offset, width, height, stage, 
+                                      prev_state, curr_state, round_key)
+                y_offset += height + 30
+                continue
+            if stage in ("Отбеливание на входе", "Отбеливание на выходе"):
+                WhiteningVisualizer(scene, x_margin, y_offset, width, 200, stage, 
+                                    prev_state, curr_state, round_key)
+                y_offset += 200 + 30
+                continue
+            round_num += 1
             
-            FeistelRoundVisualizer(scene, x_margin, y_offset, width, height, 
-                                  i, prev_state, curr_state, round_key)
+            # Функция F раунда шифрования, которому соответствует это состояние
+            index = self.rounds - round_num if decrypt else round_num - 1
+            round_func = round_func_at(self.round_func, index)
+            
+            if self.structure == LAI_MASSEY:
+                last = round_num == (1 if decrypt else self.rounds)
+                LaiMasseyRoundVisualizer(scene, x_margin, y_offset, width, height, round_num, 
+                                         prev_state, curr_state, round_key, 
+                                         round_func, decrypt, last)
+            else:
+                inverse = decrypt and 2 * self.split != len(self.original_block)
+                label = None if stage.startswith("Раунд ") else stage
+                FeistelRoundVisualizer(scene, x_margin, y_offset, width, height, 
+                                      round_num, prev_state, curr_state, round_key, self.split, 
+                                      wiring, round_func, inverse, label)
             
             y_offset += height + 30
         
-        # Устанавливаем размер сцены
-        scene.setSceneRect(0, 0, width + 2*x_margin, y_offset + y_margin)
+        return y_offset
 
 class FeistelNetworkGUI(QMainWindow):
     """Основной класс графического интерфейса приложения"""
@@ -457,34 +3705,186 @@ class FeistelNetworkGUI(QMainWindow):
         self.text_input.setText("leshaartamonovdvoeshnik")
         controls_layout.addWidget(self.text_input, 0, 1, 1, 3)
         
-        # Поле ввода ключа
-        controls_layout.addWidget(QLabel("Ключ:"), 1, 0)
+        # Выбор шифра: сеть, настроенная полями ниже, или стандартный шифр
+        controls_layout.addWidget(QLabel("Шифр:"), 1, 0)
+        self.cipher_input = QComboBox()
+        self.cipher_input.addItem("Настраиваемая сеть Фейстеля", None)
+        for name in CIPHERS:
+            self.cipher_input.addItem(name, name)
+        self.cipher_input.currentIndexChanged.connect(self.cipher_changed)
+        controls_layout.addWidget(self.cipher_input, 1, 1)
+        
+        self.cipher_test_button = QPushButton("Проверить по известным ответам")
+        self.cipher_test_button.clicked.connect(self.cipher_self_test)
+        controls_layout.addWidget(self.cipher_test_button, 1, 2)
+        
+        # Каскад EDE из двух или трех проходов шифра с независимыми ключами
+        cascade_layout = QHBoxLayout()
+        cascade_layout.addWidget(QLabel("Каскад:"))
+        self.cascade_input = QComboBox()
+        self.cascade_input.addItem("Без каскада", None)
+        for count in CASCADE_KEYS:
+            self.cascade_input.addItem(f"EDE, {count} ключа", count)
+        self.cascade_input.currentIndexChanged.connect(self.cascade_changed)
+        cascade_layout.addWidget(self.cascade_input)
+        controls_layout.addLayout(cascade_layout, 1, 3)
+        
+        # Поля ввода ключа и ключей k2, k3 каскада
+        controls_layout.addWidget(QLabel("Ключ:"), 2, 0)
+        key_layout = QHBoxLayout()
         self.key_input = QLineEdit()
         self.key_input.setPlaceholderText("Введите ключ")
         self.key_input.setText("nezachet")
-        controls_layout.addWidget(self.key_input, 1, 1)
+        key_layout.addWidget(self.key_input)
+        self.cascade_key_inputs = []
+        for number in (2, 3):
+            key_input = QLineEdit()
+            key_input.setPlaceholderText(f"Ключ k{number}")
+            key_input.setEnabled(False)
+            key_layout.addWidget(key_input)
+            self.cascade_key_inputs.append(key_input)
+        controls_layout.addLayout(key_layout, 2, 1)
         
         # Выбор количества раундов
-        controls_layout.addWidget(QLabel("Раунды:"), 1, 2)
+        controls_layout.addWidget(QLabel("Раунды:"), 2, 2)
         self.rounds_input = QSpinBox()
         self.rounds_input.setRange(1, 20)
         self.rounds_input.setValue(10)
-        controls_layout.addWidget(self.rounds_input, 1, 3)
+        controls_layout.addWidget(self.rounds_input, 2, 3)
+        
+        # Выбор размера блока
+        controls_layout.addWidget(QLabel("Размер блока:"), 3, 0)
+        self.block_size_input = QComboBox()
+        for size in BLOCK_SIZES:
+            self.block_size_input.addItem(f"{size} бит", size)
+        self.block_size_input.setCurrentIndex(BLOCK_SIZES.index(64))
+        controls_layout.addWidget(self.block_size_input, 3, 1)
+        
+        # Выбор блока сообщения для визуализации
+        controls_layout.addWidget(QLabel("Блок для визуализации:"), 3, 2)
+        self.block_index_input = QSpinBox()
+        self.block_index_input.setRange(1, 1)
+        controls_layout.addWidget(self.block_index_input, 3, 3)
+        
+        # Выбор схемы дополнения
+        controls_layout.addWidget(QLabel("Дополнение:"), 4, 0)
+        self.padding_input = QComboBox()
+        self.padding_input.addItems(list(PADDING_SCHEMES))
+        controls_layout.addWidget(self.padding_input, 4, 1)
+        
+        # Выбор режима работы
+        controls_layout.addWidget(QLabel("Режим:"), 4, 2)
+        self.mode_input = QComboBox()
+        self.mode_input.addItems(list(MODES))
+        controls_layout.addWidget(self.mode_input, 4, 3)
+        
+        # Вектор инициализации (начальное значение счетчика для CTR)
+        controls_layout.addWidget(QLabel("IV (HEX):"), 5, 0)
+        self.iv_input = QLineEdit()
+        self.iv_input.setPlaceholderText("Пусто - сгенерировать случайный IV при шифровании")
+        controls_layout.addWidget(self.iv_input, 5, 1)
+        
+        # Твик: например, имя столбца или номер строки таблицы базы данных
+        controls_layout.addWidget(QLabel("Твик:"), 5, 2)
+        self.tweak_input = QLineEdit()
+        self.tweak_input.setPlaceholderText("Пусто - без твика")
+        controls_layout.addWidget(self.tweak_input, 5, 3)
+        
+        # Выбор функции раунда F
+        controls_layout.addWidget(QLabel("Функция F:"), 6, 0)
+        self.round_func_input = QComboBox()
+        self.round_func_input.addItems(list(ROUND_FUNCTIONS))
+        controls_layout.addWidget(self.round_func_input, 6, 1)
+        
+        # Сдвиги по раундам для функции с циклическим сдвигом
+        controls_layout.addWidget(QLabel("Сдвиги по раундам:"), 8, 0)
+        self.shifts_input = QLineEdit()
+        self.shifts_input.setPlaceholderText("Биты через запятую, отрицательные - вправо (например: 1, 3, -5)")
+        self.shifts_input.setText("1")
+        controls_layout.addWidget(self.shifts_input, 8, 1, 1, 3)
+        
+        # Выбор алгоритма расписания ключей
+        controls_layout.addWidget(QLabel("Расписание ключей:"), 6, 2)
+        self.schedule_input = QComboBox()
+        self.schedule_input.addItems(list(KEY_SCHEDULES))
+        controls_layout.addWidget(self.schedule_input, 6, 3)
+        
+        # Доля левой части блока: 50% - сбалансированная сеть,
+        # меньше - с тяжелым источником, больше - с тяжелой целью
+        controls_layout.addWidget(QLabel("Левая часть блока:"), 7, 0)
+        self.split_input = QSpinBox()
+        self.split_input.setRange(1, 99)
+        self.split_input.setSuffix(" %")
+        self.split_input.setValue(50)
+        controls_layout.addWidget(self.split_input, 7, 1)
+        
+        # Выбор структуры сети и числа ветвей обобщенной сети
+        controls_layout.addWidget(QLabel("Структура сети:"), 7, 2)
+        structure_layout = QHBoxLayout()
+        self.structure_input = QComboBox()
+        self.structure_input.addItem("Классическая", None)
+        for name in GFN_TYPES:
+            self.structure_input.addItem(f"Обобщенная {name}", name)
+        self.structure_input.addItem(LAI_MASSEY, LAI_MASSEY)
+        structure_layout.addWidget(self.structure_input)
+        self.branches_input = QSpinBox()
+        self.branches_input.setRange(2, 16)
+        self.branches_input.setValue(4)
+        self.branches_input.setSuffix(" ветвей")
+        structure_layout.addWidget(self.branches_input)
+        controls_layout.addLayout(structure_layout, 7, 3)
+        
+        # Начальная и конечная перестановки битов блока (номера битов с единицы)
+        controls_layout.addWidget(QLabel("Перестановка IP:"), 9, 0)
+        self.ip_input, layout = self.create_permutation_input("Пусто - без начальной перестановки")
+        controls_layout.addLayout(layout, 9, 1)
+        
+        controls_layout.addWidget(QLabel("Перестановка FP:"), 9, 2)
+        self.fp_input, layout = self.create_permutation_input("Пусто - обратная к IP")
+        controls_layout.addLayout(layout, 9, 3)
+        
+        # P-блок, переставляющий биты выхода функции F
+        controls_layout.addWidget(QLabel("P-блок F:"), 10, 0)
+        self.pbox_input, layout = self.create_permutation_input("Пусто - без P-блока; таблица на размер правой части")
+        controls_layout.addLayout(layout, 10, 1, 1, 3)
+        
+        # Отбеливание: ключи выводятся из мастер-ключа или задаются в HEX
+        controls_layout.addWidget(QLabel("Отбеливание:"), 11, 0)
+        self.whitening_input = QComboBox()
+        self.whitening_input.addItems(list(WHITENING_MODES))
+        controls_layout.addWidget(self.whitening_input, 11, 1)
+        
+        whitening_keys_layout = QHBoxLayout()
+        self.pre_whitening_input = QLineEdit()
+        self.pre_whitening_input.setPlaceholderText("K_pre (HEX)")
+        whitening_keys_layout.addWidget(self.pre_whitening_input)
+        self.post_whitening_input = QLineEdit()
+        self.post_whitening_input.setPlaceholderText("K_post (HEX)")
+        whitening_keys_layout.addWidget(self.post_whitening_input)
+        controls_layout.addLayout(whitening_keys_layout, 11, 2, 1, 2)
         
         # Кнопки
         self.encrypt_button = QPushButton("Зашифровать")
         self.encrypt_button.clicked.connect(self.encrypt_action)
-        controls_layout.addWidget(self.encrypt_button, 2, 1)
+        controls_layout.addWidget(self.encrypt_button, 12, 1)
         
         self.decrypt_button = QPushButton("Дешифровать")
         self.decrypt_button.clicked.connect(self.decrypt_action)
-        controls_layout.addWidget(self.decrypt_button, 2, 2)
+        controls_layout.addWidget(self.decrypt_button, 12, 2)
         
         # Поле вывода результата
-        controls_layout.addWidget(QLabel("Результат:"), 3, 0)
+        controls_layout.addWidget(QLabel("Результат:"), 13, 0)
         self.result_output = QTextEdit()
         self.result_output.setReadOnly(True)
-        controls_layout.addWidget(self.result_output, 3, 1, 1, 3)
+        controls_layout.addWidget(self.result_output, 13, 1, 1, 3)
+        
+        # Поля настройки сети, не используемые стандартными шифрами
+        self.custom_cipher_widgets = [
+            self.rounds_input, self.block_size_input, self.round_func_input, self.schedule_input, 
+            self.split_input, self.structure_input, self.branches_input, self.shifts_input, 
+            self.ip_input, self.fp_input, self.pbox_input, self.whitening_input, 
+            self.pre_whitening_input, self.post_whitening_input, self.tweak_input,
+        ]
         
         # Графическая сцена для визуализации
         self.scene = QGraphicsScene()
@@ -497,6 +3897,21 @@ class FeistelNetworkGUI(QMainWindow):
         # Устанавливаем начальные размеры сплиттера
         splitter.setSizes([200, 600])
         
+        # Вкладка с наборами S-блоков
+        self.create_sbox_tab()
+        
+        # Вкладка шифрования с сохранением формата
+        self.create_fpe_tab()
+        
+        # Вкладка режимов работы и имитовставки ГОСТ Р 34.13-2015
+        self.create_gost_modes_tab()
+        
+        # Вкладка перестановки произвольного домена [0, N)
+        self.create_domain_tab()
+        
+        # Вкладка с различителями конструкции Люби-Ракоффа
+        self.create_luby_rackoff_tab()
+        
         # Вкладка "О программе"
         about_tab = QWidget()
         self.tabs.addTab(about_tab, "О программе")
@@ -511,6 +3926,7 @@ class FeistelNetworkGUI(QMainWindow):
         
         <h2>Принцип работы:</h2>
         <ol>
+            <li>Сообщение разбивается на блоки фиксированного размера (32, 64 или 128 бит), каждый блок шифруется независимо.</li>
             <li>Входной блок данных делится на две равные части: левую (L) и правую (R).</li>
             <li>В каждом раунде шифрования к правой части применяется функция преобразования F с использованием ключа раунда.</li>
             <li>Результат операции F XOR-ится с левой частью, формируя новую правую часть.</li>
@@ -521,12 +3937,575 @@ class FeistelNetworkGUI(QMainWindow):
         <h2>Особенности:</h2>
         <ul>
             <li>Дешифрование выполняется тем же алгоритмом, но с обратным порядком ключей.</li>
+            <li>Блоки обрабатываются в одном из режимов: ECB, CBC, CFB, OFB или CTR. В ECB одинаковые блоки открытого текста дают одинаковые блоки шифртекста.</li>
+            <li>Последний блок дополняется по схеме PKCS#7, ISO/IEC 7816-4, ANSI X.923 или ISO 10126; при дешифровании дополнение проверяется и удаляется.</li>
+            <li>Ключ любой длины приводится к ширине половины блока: длинный ключ сжимается по XOR, короткий повторяется.</li>
+            <li>В несбалансированной сети левая (целевая) и правая (исходная) части имеют разный размер; 
+            дешифрование выполняется обращением раундов в обратном порядке.</li>
+            <li>Обобщенные сети Фейстеля (Type-1, Type-2, Type-3, SM4) делят блок на 4, 8 и более ветвей; 
+            после каждого раунда ветви циклически сдвигаются.</li>
+            <li>Схема Лая-Мэсси (IDEA, FOX) - другой способ построить обратимый шифр из необратимой F: 
+            F вычисляется от L ⊕ R и добавляется к обеим половинам, а ортоморфизм σ не дает 
+            разности половин оставаться неизменной.</li>
+            <li>Ключи раундов вырабатываются по одному из расписаний: перестановка байтов, циклический сдвиг 
+            мастер-ключа, порядок ГОСТ 28147-89 (K1..K8 трижды, затем в обратном порядке), РСЛОС 
+            или сдвиг с константами раундов.</li>
+            <li>Слой подстановки S-блоками функций "S-блок" и "ГОСТ" настраивается на вкладке "S-блоки": 
+            узлы замены ГОСТ (param-Z, тестовые, КриптоПро A-D), S-блоки DES, PRESENT 
+            или собственный набор, введенный вручную или загруженный из файла.</li>
+            <li>Перестановки битов задаются таблицами (номера битов с единицы, как в стандарте DES): 
+            начальная IP и конечная FP вокруг раундов и P-блок на выходе функции F. 
+            Таблицы проверяются на то, что они действительно являются перестановками.</li>
+            <li>Отбеливание (DES-X, Twofish) добавляет по XOR ключ K_pre к блоку до первого раунда 
+            и ключ K_post после финальной перестановки. Ключи выводятся из мастер-ключа 
+            или задаются отдельно.</li>
+            <li>Твик (как в FF1/FF3) подмешивается в ключи раундов: половина T_L - в четные раунды, 
+            T_R - в нечетные. Одно и то же значение с разными твиками (например, для разных 
+            столбцов или строк базы данных) шифруется по-разному при одном ключе.</li>
+            <li>На вкладке "FPE" реализовано шифрование с сохранением формата FF1 (NIST SP 800-38G): 
+            10 раундов несбалансированной сети Фейстеля над строками цифр по произвольному 
+            основанию, F - CBC-MAC на AES. Номер карты остается строкой цифр той же длины.</li>
+            <li>FF3-1 (NIST SP 800-38G Rev.1) - 8 раундов с 56-битным твиком; проверяются длина твика 
+            и границы домена: radix^n не меньше 10^6 и n не больше 2·log_radix(2^96).</li>
+            <li>На вкладке "Домен [0, N)" числа из произвольного диапазона шифруются сетью Фейстеля 
+            на ближайшей четной разрядности с повторным шифрованием (cycle walking), пока результат 
+            не попадет в домен. Так получаются перестановки малых доменов - для маскировки 
+            идентификаторов и перемешивания без таблицы подстановки.</li>
+            <li>Функция F "HMAC-SHA256 (ПСФ)" - настоящая ключевая псевдослучайная функция. 
+            По теореме Люби-Ракоффа 3 раунда с такой F дают псевдослучайную перестановку, 
+            4 раунда - сильную; вкладка "Люби-Ракофф" запускает стандартные различители 
+            против 2, 3 и 4 раундов.</li>
+            <li>Стандартные шифры собраны на той же сети Фейстеля: DES - начальная перестановка IP, 
+            16 раундов с расширением E, S-блоками S1..S8 и перестановкой P, расписание ключей 
+            PC-1/PC-2 и конечная перестановка FP. Ключ задается в HEX, реализация проверяется 
+            по известным ответам.</li>
+            <li>"Магма" (ГОСТ Р 34.12-2015, ГОСТ 28147-89) - 32 раунда с 32-битными половинами, 
+            сложением по модулю 2^32, узлами замены param-Z и сдвигом на 11 битов; ключи 
+            K1..K8 трижды, затем K8..K1. Раунды подписаны как в стандарте: G[K1], ..., G*[K32]; 
+            проверяется по контрольному примеру приложения А.</li>
+            <li>Blowfish - 16 раундов с P-массивом и четырьмя S-блоками, зависящими от ключа. Начальные 
+            значения - цифры π, вычисленные по формуле Мэчина; расписание ключей выполняет 521 шифрование 
+            и показывается перед раундами. Раунд переписан для общей сети: P1 и P18 - отбеливание, 
+            обмен половин - перестановки IP/FP. Проверяется по известным ответам Eric Young.</li>
+            <li>TEA и XTEA - 32 цикла по два раунда над 32-битными половинами с константой δ = 0x9e3779b9. 
+            В отличие от учебной F, они используют только сложение, сдвиги и XOR (ARX), а выход F 
+            прибавляется по модулю 2^32; каждый цикл показан отдельным раундом с промежуточными значениями.</li>
+            <li>Семейство Simon от 32/64 до 128/256 - раунд (x, y) -> (y ⊕ F(x) ⊕ k, x) с F(x) = (S^1 x & S^8 x) ⊕ S^2 x 
+            и расписанием ключей на последовательностях z0..z4. Каждый размер блока и ключа - отдельный шифр 
+            в списке, проверяемый по примерам из описания; функция F Simon доступна и для настраиваемой сети.</li>
+            <li>На вкладке "ГОСТ Р 34.13" реализованы режимы ГОСТ Р 34.13-2015 (простая замена, 
+            гаммирование, OFB, простая замена с зацеплением, CFB) с параметрами s и m, процедуры 
+            дополнения 1-3 и выработка имитовставки; результаты сверяются с примерами стандарта.</li>
+            <li>Каскад EDE (E_k1, D_k2, E_k3, как в Triple DES) применяется к любому шифру вкладки. 
+            С двумя ключами k3 = k1; длина ключа растет вдвое или втрое, но трехключевой каскад 
+            встреча посередине ослабляет до двух ключей.</li>
             <li>Количество раундов влияет на криптостойкость шифра.</li>
-            <li>Функция F может различаться в разных реализациях шифров на основе сети Фейстеля.</li>
+            <li>Функция F может различаться в разных реализациях шифров на основе сети Фейстеля. 
+            Доступны классическая функция (XOR, инверсия, сдвиг), функция с циклическим сдвигом 
+            всей половины блока (сдвиг задается для каждого раунда), подстановка S-блоком, ARX, 
+            функция в стиле DES (расширение, S-блоки, перестановка), в стиле ГОСТ 28147-89 
+            (сложение по модулю 2^32, S-блоки, сдвиг на 11) и HMAC-SHA256.</li>
         </ul>
         """)
         about_layout.addWidget(about_text)
     
+    def create_permutation_input(self, placeholder):
+        """
+        Создает поле таблицы перестановки битов со списком готовых таблиц.
+        
+        Returns:
+            Поле ввода таблицы и слой с полем и списком таблиц
+        """
+        layout = QHBoxLayout()
+        table_input = QLineEdit()
+        table_input.setPlaceholderText(placeholder)
+        layout.addWidget(table_input)
+        
+        preset_input = QComboBox()
+        preset_input.addItem("Своя таблица")
+        preset_input.addItems(list(PERMUTATION_TABLES))
+        preset_input.textActivated.connect(
+            lambda name: table_input.setText(' '.join(str(x) for x in PERMUTATION_TABLES[name])) 
+            if name in PERMUTATION_TABLES else None)
+        layout.addWidget(preset_input)
+        
+        return table_input, layout
+    
+    def create_fpe_tab(self):
+        """Создает вкладку шифрования с сохранением формата (FF1, FF3-1)"""
+        fpe_tab = QWidget()
+        self.tabs.addTab(fpe_tab, "FPE")
+        fpe_layout = QGridLayout(fpe_tab)
+        
+        fpe_layout.addWidget(QLabel("Метод:"), 0, 0)
+        self.fpe_method_input = QComboBox()
+        self.fpe_method_input.addItems(list(FPE_METHODS))
+        fpe_layout.addWidget(self.fpe_method_input, 0, 1)
+        
+        # Алфавит задает основание: номер символа в алфавите - его цифра
+        fpe_layout.addWidget(QLabel("Алфавит:"), 0, 2)
+        self.fpe_alphabet_input = QLineEdit()
+        self.fpe_alphabet_input.setText(FPE_ALPHABET[:10])
+        fpe_layout.addWidget(self.fpe_alphabet_input, 0, 3)
+        
+        fpe_layout.addWidget(QLabel("Ключ AES (HEX):"), 1, 0)
+        self.fpe_key_input = QLineEdit()
+        self.fpe_key_input.setText("2b7e151628aed2a6abf7158809cf4f3c")
+        fpe_layout.addWidget(self.fpe_key_input, 1, 1)
+        
+        fpe_layout.addWidget(QLabel("Твик (HEX):"), 1, 2)
+        self.fpe_tweak_input = QLineEdit()
+        self.fpe_tweak_input.setPlaceholderText("Пусто - без твика")
+        fpe_layout.addWidget(self.fpe_tweak_input, 1, 3)
+        
+        fpe_layout.addWidget(QLabel("Текст:"), 2, 0)
+        self.fpe_text_input = QLineEdit()
+        self.fpe_text_input.setPlaceholderText("Например, номер карты или телефона")
+        self.fpe_text_input.setText("4111111111111111")
+        fpe_layout.addWidget(self.fpe_text_input, 2, 1, 1, 3)
+        
+        fpe_encrypt_button = QPushButton("Зашифровать")
+        fpe_encrypt_button.clicked.connect(lambda: self.process_fpe(False))
+        fpe_layout.addWidget(fpe_encrypt_button, 3, 1)
+        
+        fpe_decrypt_button = QPushButton("Дешифровать")
+        fpe_decrypt_button.clicked.connect(lambda: self.process_fpe(True))
+        fpe_layout.addWidget(fpe_decrypt_button, 3, 2)
+        
+        fpe_test_button = QPushButton("Проверить по примерам NIST")
+        fpe_test_button.clicked.connect(self.fpe_self_test)
+        fpe_layout.addWidget(fpe_test_button, 3, 3)
+        
+        # Результат и половины A, B после каждого раунда
+        fpe_layout.addWidget(QLabel("Результат:"), 4, 0)
+        self.fpe_output = QTextEdit()
+        self.fpe_output.setReadOnly(True)
+        self.fpe_output.setFont(QFont("Courier", 9))
+        fpe_layout.addWidget(self.fpe_output, 4, 1, 1, 3)
+    
+    def process_fpe(self, decrypt=False):
+        """Шифрует или дешифрует строку выбранным методом FPE"""
+        cipher, _ = FPE_METHODS[self.fpe_method_input.currentText()]
+        alphabet = self.fpe_alphabet_input.text()
+        text = self.fpe_text_input.text()
+        
+        if len(set(alphabet)) != len(alphabet) or len(alphabet) < 2:
+            self.fpe_output.setText("Ошибка: Алфавит должен содержать не меньше двух различных символов")
+            return
+        try:
+            key = list(bytes.fromhex(self.fpe_key_input.text()))
+            tweak = list(bytes.fromhex(self.fpe_tweak_input.text()))
+        except ValueError:
+            self.fpe_output.setText("Ошибка: Ключ и твик задаются в HEX")
+            return
+        
+        trace = []
+        try:
+            digits = text_to_digits(text, alphabet)
+            result = cipher(key, tweak, digits, len(alphabet), decrypt, trace)
+        except ValueError as e:
+            self.fpe_output.setText(f"Ошибка: {e}")
+            return
+        
+        result_text = digits_to_text(result, alphabet)
+        rounds_text = '\n'.join([f"{name:<15} A = {digits_to_text(a, alphabet):<20} "
+                                  f"B = {digits_to_text(b, alphabet)}" for name, a, b in trace])
+        self.fpe_output.setText(f"Результат: {result_text}\n"
+                                f"Основание: {len(alphabet)}, длина: {len(result)}\n\n{rounds_text}")
+    
+    def fpe_self_test(self):
+        """Проверяет выбранный метод FPE по известным примерам"""
+        cipher, vectors = FPE_METHODS[self.fpe_method_input.currentText()]
+        self.fpe_output.setText('\n'.join(run_fpe_test_vectors(cipher, vectors)))
+    
+    def create_gost_modes_tab(self):
+        """Создает вкладку режимов работы и имитовставки ГОСТ Р 34.13-2015"""
+        gost_tab = QWidget()
+        self.tabs.addTab(gost_tab, "ГОСТ Р 34.13")
+        gost_layout = QGridLayout(gost_tab)
+        
+        gost_layout.addWidget(QLabel("Шифр:"), 0, 0)
+        self.gost_cipher_input = QComboBox()
+        self.gost_cipher_input.addItems(list(CIPHERS))
+        self.gost_cipher_input.setCurrentText("Магма")
+        gost_layout.addWidget(self.gost_cipher_input, 0, 1)
+        
+        gost_layout.addWidget(QLabel("Ключ (HEX):"), 0, 2)
+        self.gost_key_input = QLineEdit()
+        self.gost_key_input.setText(GOST_MODE_EXAMPLE_KEY)
+        gost_layout.addWidget(self.gost_key_input, 0, 3)
+        
+        gost_layout.addWidget(QLabel("Режим:"), 1, 0)
+        self.gost_mode_input = QComboBox()
+        self.gost_mode_input.addItems(list(GOST_MODES))
+        self.gost_mode_input.currentIndexChanged.connect(self.gost_mode_changed)
+        gost_layout.addWidget(self.gost_mode_input, 1, 1)
+        
+        gost_layout.addWidget(QLabel("Дополнение:"), 1, 2)
+        self.gost_padding_input = QComboBox()
+        self.gost_padding_input.addItems(list(GOST_PADDINGS))
+        gost_layout.addWidget(self.gost_padding_input, 1, 3)
+        
+        # Параметры режимов: s - длина гаммы и имитовставки, m - длина регистра
+        gost_layout.addWidget(QLabel("s (бит):"), 2, 0)
+        self.gost_s_input = QSpinBox()
+        self.gost_s_input.setRange(8, 128)
+        self.gost_s_input.setSingleStep(8)
+        gost_layout.addWidget(self.gost_s_input, 2, 1)
+        
+        gost_layout.addWidget(QLabel("m (бит):"), 2, 2)
+        self.gost_m_input = QSpinBox()
+        self.gost_m_input.setRange(8, 1024)
+        self.gost_m_input.setSingleStep(8)
+        gost_layout.addWidget(self.gost_m_input, 2, 3)
+        
+        gost_layout.addWidget(QLabel("IV (HEX):"), 3, 0)
+        self.gost_iv_input = QLineEdit()
+        gost_layout.addWidget(self.gost_iv_input, 3, 1, 1, 3)
+        
+        gost_layout.addWidget(QLabel("Данные (HEX):"), 4, 0)
+        self.gost_data_input = QTextEdit()
+        self.gost_data_input.setText(GOST_MODE_EXAMPLE_TEXT)
+        gost_layout.addWidget(self.gost_data_input, 4, 1, 1, 3)
+        
+        gost_encrypt_button = QPushButton("Зашифровать")
+        gost_encrypt_button.clicked.connect(lambda: self.process_gost_mode(False))
+        gost_layout.addWidget(gost_encrypt_button, 5, 0)
+        
+        gost_decrypt_button = QPushButton("Расшифровать")
+        gost_decrypt_button.clicked.connect(lambda: self.process_gost_mode(True))
+        gost_layout.addWidget(gost_decrypt_button, 5, 1)
+        
+        gost_mac_button = QPushButton("Имитовставка")
+        gost_mac_button.clicked.connect(self.process_gost_mac)
+        gost_layout.addWidget(gost_mac_button, 5, 2)
+        
+        gost_test_button = QPushButton("Проверить по примерам стандарта")
+        gost_test_button.clicked.connect(self.gost_self_test)
+        gost_layout.addWidget(gost_test_button, 5, 3)
+        
+        gost_layout.addWidget(QLabel("Результат:"), 6, 0)
+        self.gost_output = QTextEdit()
+        self.gost_output.setReadOnly(True)
+        self.gost_output.setFont(QFont("Courier", 9))
+        gost_layout.addWidget(self.gost_output, 6, 1, 1, 3)
+        
+        self.gost_mode_changed()
+    
+    def gost_mode_changed(self):
+        """Подставляет параметры примера стандарта и включает используемые режимом поля"""
+        mode = self.gost_mode_input.currentText()
+        _, padded, _, uses_m = GOST_MODES[mode]
+        s, m, iv, _ = GOST_MODE_EXAMPLES[mode]
+        self.gost_s_input.setValue(s)
+        self.gost_m_input.setValue(m)
+        self.gost_iv_input.setText(iv)
+        # Параметр s нужен и имитовставке, поэтому его поле не отключается
+        self.gost_padding_input.setEnabled(padded)
+        self.gost_m_input.setEnabled(uses_m)
+        self.gost_iv_input.setEnabled(bool(iv))
+    
+    def gost_setup(self):
+        """
+        Читает шифр, ключ и данные вкладки ГОСТ Р 34.13.
+        
+        Returns:
+            Функция cipher(block, decrypt), размер блока в байтах и данные
+        """
+        name = self.gost_cipher_input.currentText()
+        cipher, _, _ = self.standard_cipher_setup(name, [self.gost_key_input.text()])
+        try:
+            data = list(bytes.fromhex(self.gost_data_input.toPlainText()))
+        except ValueError:
+            raise ValueError("Данные задаются в HEX")
+        return cipher, block_bytes(CIPHERS[name]["block_size"]), data
+    
+    def process_gost_mode(self, decrypt=False):
+        """Зашифровывает или расшифровывает данные в выбранном режиме ГОСТ Р 34.13-2015"""
+        mode = self.gost_mode_input.currentText()
+        mode_func, padded, _, _ = GOST_MODES[mode]
+        procedure = GOST_PADDINGS[self.gost_padding_input.currentText()]
+        s = self.gost_s_input.value()
+        m = self.gost_m_input.value()
+        try:
+            cipher, size, data = self.gost_setup()
+            try:
+                iv = list(bytes.fromhex(self.gost_iv_input.text()))
+            except ValueError:
+                raise ValueError("IV задается в HEX")
+            if padded and not decrypt:
+                data = gost_pad(data, size, procedure)
+            result = mode_func(data, cipher, decrypt, size, s, m, iv)
+            if padded and decrypt:
+                result = gost_unpad(result, size, procedure)
+        except ValueError as e:
+            self.gost_output.setText(f"Ошибка: {e}")
+            return
+        
+        # Результат по блокам, как в примерах стандарта
+        blocks = '\n'.join([bytes(result[i:i + size]).hex() for i in range(0, len(result), size)])
+        params = f"n = {8 * size}"
+        if GOST_MODES[mode][2]:
+            params += f", s = {s}"
+        if GOST_MODES[mode][3]:
+            params += f", m = {m}"
+        self.gost_output.setText(f"{mode}, {params}\n{blocks}\n\nHEX: {bytes(result).hex()}")
+    
+    def process_gost_mac(self):
+        """Вырабатывает имитовставку длины s для данных вкладки"""
+        s = self.gost_s_input.value()
+        try:
+            cipher, size, data = self.gost_setup()
+            k1, k2 = gost_mac_keys(cipher, size)
+            mac = gost_mac(data, cipher, size, s)
+        except ValueError as e:
+            self.gost_output.setText(f"Ошибка: {e}")
+            return
+        self.gost_output.setText(f"K1: {bytes(k1).hex()}\nK2: {bytes(k2).hex()}\n"
+                                 f"Имитовставка (s = {s}): {bytes(mac).hex()}")
+    
+    def gost_self_test(self):
+        """Проверяет режимы и имитовставку по примерам ГОСТ Р 34.13-2015 для Магмы"""
+        lines = run_gost_mode_examples(CIPHERS["Магма"]["crypt"])
+        self.gost_output.setText("Примеры ГОСТ Р 34.13-2015 (Магма):\n" + '\n'.join(lines))
+    
+    def create_domain_tab(self):
+        """Создает вкладку шифрования чисел из диапазона [0, N) с cycle walking"""
+        domain_tab = QWidget()
+        self.tabs.addTab(domain_tab, "Домен [0, N)")
+        domain_layout = QGridLayout(domain_tab)
+        
+        domain_layout.addWidget(QLabel("Размер домена N:"), 0, 0)
+        self.domain_size_input = QLineEdit()
+        self.domain_size_input.setText("1000000")
+        domain_layout.addWidget(self.domain_size_input, 0, 1)
+        
+        domain_layout.addWidget(QLabel("Число:"), 0, 2)
+        self.domain_value_input = QLineEdit()
+        self.domain_value_input.setText("12345")
+        domain_layout.addWidget(self.domain_value_input, 0, 3)
+        
+        domain_layout.addWidget(QLabel("Ключ:"), 1, 0)
+        self.domain_key_input = QLineEdit()
+        self.domain_key_input.setText("nezachet")
+        domain_layout.addWidget(self.domain_key_input, 1, 1)
+        
+        domain_layout.addWidget(QLabel("Раунды:"), 1, 2)
+        self.domain_rounds_input = QSpinBox()
+        self.domain_rounds_input.setRange(1, 20)
+        self.domain_rounds_input.setValue(10)
+        domain_layout.addWidget(self.domain_rounds_input, 1, 3)
+        
+        domain_layout.addWidget(QLabel("Функция F:"), 2, 0)
+        self.domain_round_func_input = QComboBox()
+        self.domain_round_func_input.addItems(list(ROUND_FUNCTIONS))
+        domain_layout.addWidget(self.domain_round_func_input, 2, 1)
+        
+        domain_layout.addWidget(QLabel("Расписание ключей:"), 2, 2)
+        self.domain_schedule_input = QComboBox()
+        self.domain_schedule_input.addItems(list(KEY_SCHEDULES))
+        domain_layout.addWidget(self.domain_schedule_input, 2, 3)
+        
+        domain_encrypt_button = QPushButton("Зашифровать")
+        domain_encrypt_button.clicked.connect(lambda: self.process_domain(False))
+        domain_layout.addWidget(domain_encrypt_button, 3, 1)
+        
+        domain_decrypt_button = QPushButton("Дешифровать")
+        domain_decrypt_button.clicked.connect(lambda: self.process_domain(True))
+        domain_layout.addWidget(domain_decrypt_button, 3, 2)
+        
+        # Перемешивание: образы первых чисел домена без таблицы подстановки
+        domain_shuffle_button = QPushButton("Перемешать домен")
+        domain_shuffle_button.clicked.connect(self.shuffle_domain)
+        domain_layout.addWidget(domain_shuffle_button, 3, 3)
+        
+        domain_layout.addWidget(QLabel("Результат:"), 4, 0)
+        self.domain_output = QTextEdit()
+        self.domain_output.setReadOnly(True)
+        self.domain_output.setFont(QFont("Courier", 9))
+        domain_layout.addWidget(self.domain_output, 4, 1, 1, 3)
+    
+    def domain_params(self):
+        """Считывает параметры вкладки домена: (N, ключ, раунды, F, расписание)"""
+        try:
+            n = int(self.domain_size_input.text())
+        except ValueError:
+            raise ValueError("Размер домена N должен быть целым числом")
+        key = list(self.domain_key_input.text().encode('utf-8'))
+        if not key:
+            raise ValueError("Пожалуйста, введите ключ")
+        return (n, key, self.domain_rounds_input.value(), 
+                ROUND_FUNCTIONS[self.domain_round_func_input.currentText()], 
+                KEY_SCHEDULES[self.domain_schedule_input.currentText()])
+    
+    def process_domain(self, decrypt=False):
+        """Шифрует или дешифрует число из домена [0, N) и показывает шаги cycle walking"""
+        trace = []
+        try:
+            n, key, rounds, round_func, schedule = self.domain_params()
+            try:
+                value = int(self.domain_value_input.text())
+            except ValueError:
+                raise ValueError("Число должно быть целым")
+            result = cycle_walk(value, n, key, decrypt, rounds, round_func, schedule, trace)
+        except ValueError as e:
+            self.domain_output.setText(f"Ошибка: {e}")
+            return
+        
+        half_bits = domain_half_bits(n)
+        steps = '\n'.join([f"Шаг {i + 1}: {x}" + ("" if x < n else "  (вне домена, шифруем снова)") 
+                           for i, x in enumerate(trace)])
+        self.domain_output.setText(f"Результат: {result}\n"
+                                   f"Сеть Фейстеля на {2 * half_bits} битах: домен [0, {1 << (2 * half_bits)}), "
+                                   f"N = {n}\n\n{steps}")
+    
+    def shuffle_domain(self):
+        """Показывает образы первых чисел домена: перемешивание без таблицы"""
+        try:
+            n, key, rounds, round_func, schedule = self.domain_params()
+            count = min(n, 64)
+            images = [cycle_walk(x, n, key, False, rounds, round_func, schedule) for x in range(count)]
+        except ValueError as e:
+            self.domain_output.setText(f"Ошибка: {e}")
+            return
+        
+        lines = [f"{x} -> {y}" for x, y in enumerate(images)]
+        if count < n:
+            lines.append(f"... (показаны первые {count} из {n})")
+        self.domain_output.setText('\n'.join(lines))
+    
+    def create_luby_rackoff_tab(self):
+        """Создает вкладку с демонстрацией различителей для 2, 3 и 4 раундов"""
+        lr_tab = QWidget()
+        self.tabs.addTab(lr_tab, "Люби-Ракофф")
+        lr_layout = QGridLayout(lr_tab)
+        
+        lr_layout.addWidget(QLabel("Размер блока:"), 0, 0)
+        self.lr_block_size_input = QComboBox()
+        for size in BLOCK_SIZES:
+            self.lr_block_size_input.addItem(f"{size} бит", size)
+        lr_layout.addWidget(self.lr_block_size_input, 0, 1)
+        
+        lr_layout.addWidget(QLabel("Испытаний:"), 0, 2)
+        self.lr_trials_input = QSpinBox()
+        self.lr_trials_input.setRange(10, 10000)
+        self.lr_trials_input.setValue(200)
+        lr_layout.addWidget(self.lr_trials_input, 0, 3)
+        
+        lr_button = QPushButton("Запустить различители")
+        lr_button.clicked.connect(self.run_luby_rackoff)
+        lr_layout.addWidget(lr_button, 1, 1, 1, 2)
+        
+        self.lr_output = QTextEdit()
+        self.lr_output.setReadOnly(True)
+        self.lr_output.setFont(QFont("Courier", 9))
+        lr_layout.addWidget(self.lr_output, 2, 0, 1, 4)
+    
+    def run_luby_rackoff(self):
+        """Запускает различители и показывает долю успехов для каждого оракула"""
+        block_size = self.lr_block_size_input.currentData()
+        trials = self.lr_trials_input.value()
+        rows = luby_rackoff_demo(block_size, trials)
+        
+        names = list(LR_DISTINGUISHERS)
+        lines = [f"F = HMAC-SHA256 с ключами раундов из keys_gen, блок {block_size} бит, "
+                 f"{trials} испытаний", "", 
+                 f"{'Оракул':<32}" + ''.join([f"{name:>26}" for name in names])]
+        for oracle, rates in rows:
+            lines.append(f"{oracle:<32}" + ''.join([f"{rates[name]:>26.0%}" for name in names]))
+        lines += ["", 
+                  f"Случайная перестановка проходит проверку с вероятностью 2^-{block_size // 2}.", 
+                  "2 раунда различимы уже по запросам на шифрование; 3 раунда - псевдослучайная", 
+                  "перестановка, но различимы с запросами на дешифрование; 4 раунда - сильная", 
+                  "псевдослучайная перестановка."]
+        self.lr_output.setText('\n'.join(lines))
+    
+    def create_sbox_tab(self):
+        """Создает вкладку выбора и редактирования набора S-блоков"""
+        sbox_tab = QWidget()
+        self.tabs.addTab(sbox_tab, "S-блоки")
+        sbox_layout = QGridLayout(sbox_tab)
+        
+        # Выбор стандартного или пользовательского набора
+        sbox_layout.addWidget(QLabel("Набор S-блоков:"), 0, 0)
+        self.sbox_set_input = QComboBox()
+        for name in SBOX_SETS:
+            self.sbox_set_input.addItem(name, name)
+        self.sbox_set_input.addItem("Пользовательский", None)
+        self.sbox_set_input.currentIndexChanged.connect(self.sbox_set_changed)
+        sbox_layout.addWidget(self.sbox_set_input, 0, 1)
+        
+        self.sbox_load_button = QPushButton("Загрузить из файла")
+        self.sbox_load_button.clicked.connect(self.load_sbox_file)
+        sbox_layout.addWidget(self.sbox_load_button, 0, 2)
+        
+        # Текст пользовательского набора: по одному S-блоку в строке
+        sbox_layout.addWidget(QLabel("S-блоки (по одному в строке, 16 или 256 значений):"), 1, 0)
+        self.sbox_text_input = QTextEdit()
+        self.sbox_text_input.setPlaceholderText("12 4 6 2 10 5 11 9 14 8 13 7 0 3 15 1\n...")
+        self.sbox_text_input.textChanged.connect(self.update_sbox_table)
+        sbox_layout.addWidget(self.sbox_text_input, 1, 1, 1, 2)
+        
+        self.sbox_status = QLabel()
+        sbox_layout.addWidget(self.sbox_status, 2, 1, 1, 2)
+        
+        # Таблица S-блоков: строки - S-блоки, столбцы - входные значения
+        self.sbox_table = QTableWidget()
+        sbox_layout.addWidget(self.sbox_table, 3, 0, 1, 3)
+        
+        self.sbox_set_changed()
+    
+    def sbox_set_changed(self):
+        """Показывает текст выбранного набора S-блоков"""
+        name = self.sbox_set_input.currentData()
+        custom = name is None
+        self.sbox_text_input.setReadOnly(not custom)
+        self.sbox_load_button.setEnabled(custom)
+        if not custom:
+            self.sbox_text_input.setText('\n'.join(' '.join(str(x) for x in box) 
+                                                   for box in SBOX_SETS[name]))
+        self.update_sbox_table()
+    
+    def selected_sboxes(self):
+        """Возвращает выбранный набор S-блоков"""
+        name = self.sbox_set_input.currentData()
+        if name is not None:
+            return SBOX_SETS[name]
+        return parse_sboxes(self.sbox_text_input.toPlainText())
+    
+    def update_sbox_table(self):
+        """Заполняет таблицу S-блоков выбранного набора"""
+        try:
+            sboxes = self.selected_sboxes()
+        except ValueError as e:
+            self.sbox_status.setText(f"Ошибка: {e}")
+            self.sbox_table.setRowCount(0)
+            return
+        
+        size = len(sboxes[0])
+        self.sbox_status.setText(f"S-блоков: {len(sboxes)}, размер: {size}")
+        self.sbox_table.setRowCount(len(sboxes))
+        self.sbox_table.setColumnCount(size)
+        self.sbox_table.setHorizontalHeaderLabels([f"{x:x}" for x in range(size)])
+        self.sbox_table.setVerticalHeaderLabels([f"S{i + 1}" for i in range(len(sboxes))])
+        for i, box in enumerate(sboxes):
+            for j, value in enumerate(box):
+                self.sbox_table.setItem(i, j, QTableWidgetItem(f"{value:x}"))
+        self.sbox_table.resizeColumnsToContents()
+    
+    def load_sbox_file(self):
+        """Загружает пользовательский набор S-блоков из текстового файла"""
+        path, _ = QFileDialog.getOpenFileName(self, "Загрузить S-блоки", "", 
+                                              "Текстовые файлы (*.txt);;Все файлы (*)")
+        if not path:
+            return
+        try:
+            sboxes = load_sboxes(path)
+        except (OSError, ValueError) as e:
+            self.sbox_status.setText(f"Ошибка: {e}")
+            return
+        self.sbox_text_input.setText('\n'.join(' '.join(str(x) for x in box) for box in sboxes))
+    
     def create_zoom_toolbar(self):
         """Создает панель инструментов для управления масштабом"""
         zoom_toolbar = QToolBar("Масштаб")
@@ -565,18 +4544,83 @@ class FeistelNetworkGUI(QMainWindow):
         # Получаем данные из полей ввода
         text = self.text_input.toPlainText()
         key = self.key_input.text()
-        rounds = self.rounds_input.value()
+        padding = self.padding_input.currentText()
+        mode = self.mode_input.currentText()
+        _, padded, needs_iv = MODES[mode]
+        cipher_name = self.cipher_input.currentData()
         
         if not text or not key:
             self.result_output.setText("Ошибка: Пожалуйста, введите текст и ключ")
             return
         
-        # Преобразуем в формат для обработки
-        block = pad_block(list(text.encode('utf-8')))
-        key_data = list(key.encode('utf-8'))
+        # Ключи k2[, k3] каскада EDE
+        keys = [key]
+        count = self.cascade_input.currentData()
+        if count:
+            keys += [field.text() for field in self.cascade_key_inputs[:count - 1]]
+            if not all(keys):
+                self.result_output.setText(f"Ошибка: Введите {count} ключа каскада EDE")
+                return
+        
+        # Блочный шифр: стандартный из CIPHERS или сеть, настроенная в полях вкладки
+        try:
+            if cipher_name is None:
+                block_size = self.block_size_input.currentData()
+                crypt, keys_text, make_visualizer = self.custom_cipher_setup(keys, block_size)
+            else:
+                block_size = CIPHERS[cipher_name]["block_size"]
+                crypt, keys_text, make_visualizer = self.standard_cipher_setup(cipher_name, keys)
+        except ValueError as e:
+            self.result_output.setText(f"Ошибка: {e}")
+            return
+        
+        # Преобразуем в формат для обработки: шифртекст задается в HEX,
+        # открытый текст - в UTF-8
+        if decrypt:
+            try:
+                data = list(bytes.fromhex(text))
+            except ValueError:
+                self.result_output.setText("Ошибка: Для дешифрования введите шифртекст в HEX")
+                return
+        else:
+            data = list(text.encode('utf-8'))
+        
+        # Вектор инициализации: при шифровании без IV генерируем случайный
+        iv = None
+        if needs_iv:
+            iv_text = self.iv_input.text().strip()
+            if not iv_text and not decrypt:
+                iv_text = os.urandom(block_bytes(block_size)).hex()
+                self.iv_input.setText(iv_text)
+            try:
+                iv = list(bytes.fromhex(iv_text))
+            except ValueError:
+                self.result_output.setText("Ошибка: IV должен быть задан в HEX")
+                return
+        
+        # Запоминаем входы блочного шифра, чтобы визуализировать выбранный блок
+        cipher_calls = []
+        def cipher(block, block_decrypt):
+            cipher_calls.append((block.copy(), block_decrypt))
+            return crypt(block, block_decrypt)
         
         # Шифруем/дешифруем
-        result_block = crypt_block(block.copy(), key_data.copy(), decrypt, rounds)
+        try:
+            if padded and not decrypt:
+                data = pad_message(data, block_size, padding)
+            result_block = apply_mode(data.copy(), cipher, decrypt, block_size, mode, iv)
+            # Байты дополнения находятся в конце открытого текста
+            if not padded:
+                pad_len = 0
+            elif decrypt:
+                plain = unpad_message(result_block, block_size, padding)
+                pad_len = len(result_block) - len(plain)
+                result_block = plain
+            else:
+                pad_len = len(data) - len(text.encode('utf-8'))
+        except ValueError as e:
+            self.result_output.setText(f"Ошибка: {e}")
+            return
         
         # Отображаем результат
         try:
@@ -587,12 +4631,220 @@ class FeistelNetworkGUI(QMainWindow):
             hex_result = ' '.join([f'{b:02x}' for b in result_block])
             self.result_output.setText(f"HEX: {hex_result}")
         
-        # Визуализируем процесс
-        visualizer = FeistelVisualizer(block, key_data, rounds, decrypt)
+        # Показываем ключи раундов, полученные по выбранному расписанию
+        self.result_output.append(keys_text)
+        
+        if not cipher_calls:
+            return
+        
+        # Визуализируем вызов блочного шифра для выбранного блока сообщения.
+        # Дополнение видно только в ECB, где на вход шифра подается сам открытый текст
+        self.block_index_input.setRange(1, len(cipher_calls))
+        index = self.block_index_input.value() - 1
+        block, block_decrypt = cipher_calls[index]
+        block_pad = pad_len if mode == "ECB" and index == len(cipher_calls) - 1 else 0
+        visualizer = make_visualizer(block, block_decrypt, block_pad)
         visualizer.visualize(self.scene)
         
         # Подгоняем вид для отображения всей сцены
         self.view.fitInView()
+    
+    def standard_cipher_setup(self, name, keys):
+        """
+        Готовит стандартный шифр из CIPHERS; ключ задается в HEX.
+        
+        Args:
+            name: Название шифра в CIPHERS
+            keys: Ключ или, для каскада EDE, ключи k1, k2[, k3]
+        
+        Returns:
+            Функция crypt(block, decrypt), текст с ключами раундов
+            и функция создания визуализатора (block, decrypt, pad_len)
+        """
+        spec = CIPHERS[name]
+        keys_data = []
+        for key in keys:
+            try:
+                key_data = list(bytes.fromhex(key))
+            except ValueError:
+                raise ValueError(f"Ключ {name} задается в HEX")
+            sizes = spec["key_sizes"]
+            if len(key_data) not in sizes:
+                allowed = f"от {sizes[0]} до {sizes[-1]}" if len(sizes) > 3 else ' или '.join(map(str, sizes))
+                raise ValueError(f"Ключ {name} должен иметь длину {allowed} байтов, а не {len(key_data)}")
+            keys_data.append(key_data)
+        
+        def crypt_pass(block, key_data, decrypt):
+            return spec["crypt"](block, key_data, decrypt)
+        
+        keys_text = ''
+        for number, key_data in enumerate(keys_data, 1):
+            round_keys = '\n'.join([f"K{i+1}: {' '.join([f'{b:02x}' for b in k])}" 
+                                    for i, k in enumerate(spec["round_keys"](key_data))])
+            owner = f", ключ k{number}" if len(keys_data) > 1 else ""
+            keys_text += f"\nКлючи раундов ({name}{owner}):\n{round_keys}"
+        
+        def make_visualizer(block, decrypt, pad_len):
+            return FeistelVisualizer(block, keys_data[0], spec["rounds"], decrypt, pad_len, 
+                                     cipher=name, cascade=keys_data[1:])
+        
+        return self.cascade_setup(crypt_pass, keys_data, keys_text, name) + (make_visualizer,)
+    
+    def cascade_setup(self, crypt_pass, keys_data, keys_text, cipher=None):
+        """
+        Связывает проход шифра с ключами: один проход или каскад EDE.
+        
+        Args:
+            crypt_pass: Функция прохода (block, key, decrypt) -> block
+            keys_data: Ключ k1 и, для каскада, ключи k2[, k3]
+            keys_text: Текст с ключами раундов
+            cipher: Стандартный шифр из CIPHERS или None
+        
+        Returns:
+            Функция crypt(block, decrypt) и текст с ключами раундов,
+            для каскада дополненный оценкой длины ключа
+        """
+        if len(keys_data) == 1:
+            return (lambda block, decrypt: crypt_pass(block, keys_data[0].copy(), decrypt)), keys_text
+        
+        bits = [cipher_key_bits(k, cipher) for k in keys_data]
+        nominal, mitm = cascade_key_bits(bits)
+        order = " → ".join(cascade_pass_name(index, decrypt) 
+                           for index, decrypt in cascade_passes(len(keys_data), False))
+        keys_text += (f"\nКаскад EDE: {order}"
+                      f"\nДлина ключа: один проход {bits[0]} бит, каскад {nominal} бит, "
+                      f"против встречи посередине {mitm} бит")
+        
+        def crypt(block, decrypt):
+            return crypt_cascade(block, keys_data, decrypt, crypt_pass)
+        
+        return crypt, keys_text
+    
+    def custom_cipher_setup(self, keys, block_size):
+        """
+        Готовит сеть Фейстеля, настроенную в полях вкладки: функцию F,
+        расписание ключей, структуру, перестановки, отбеливание и твик.
+        В каскаде EDE все проходы используют одну настройку сети,
+        а ключи отбеливания из мастер-ключа выводятся из ключа k1.
+        
+        Args:
+            keys: Ключ или, для каскада EDE, ключи k1, k2[, k3]
+            block_size: Размер блока в битах
+        
+        Returns:
+            Функция crypt(block, decrypt), текст с ключами раундов
+            и функция создания визуализатора (block, decrypt, pad_len)
+        """
+        rounds = self.rounds_input.value()
+        round_func = ROUND_FUNCTIONS[self.round_func_input.currentText()]
+        schedule = KEY_SCHEDULES[self.schedule_input.currentText()]
+        split = split_point(block_bytes(block_size), self.split_input.value())
+        structure = self.structure_input.currentData()
+        branches = self.branches_input.value()
+        keys_data = [list(key.encode('utf-8')) for key in keys]
+        tweak = list(self.tweak_input.text().encode('utf-8'))
+        
+        # Для функции с циклическим сдвигом задаем сдвиг каждого раунда
+        if round_func is f_rotate:
+            try:
+                shifts = [int(x) for x in self.shifts_input.text().split(',') if x.strip()]
+            except ValueError:
+                raise ValueError("Сдвиги задаются целыми числами через запятую")
+            if shifts:
+                round_func = rotation_round_funcs(shifts)
+        
+        # Слой подстановки использует набор S-блоков с вкладки "S-блоки"
+        if round_func in (f_sbox, f_gost):
+            round_func = functools.partial(round_func, sboxes=self.selected_sboxes())
+        
+        # Таблицы перестановок битов: IP/FP вокруг раундов и P-блок на выходе F
+        ip = parse_permutation(self.ip_input.text())
+        fp = parse_permutation(self.fp_input.text())
+        pbox = parse_permutation(self.pbox_input.text())
+        if (ip or fp) and structure is not None:
+            raise ValueError("Перестановки IP/FP поддерживаются только классической сетью")
+        if pbox:
+            round_func = pbox_round_funcs(round_func, pbox)
+        
+        # Ключи отбеливания
+        whitening = None
+        whitening_mode = self.whitening_input.currentText()
+        if whitening_mode == "Из мастер-ключа":
+            whitening = whitening_keys(keys_data[0], block_bytes(block_size))
+        elif whitening_mode == "Заданные ключи":
+            try:
+                fields = [list(bytes.fromhex(field.text())) 
+                          for field in (self.pre_whitening_input, self.post_whitening_input)]
+            except ValueError:
+                raise ValueError("Ключи отбеливания K_pre и K_post задаются в HEX")
+            if not all(fields):
+                raise ValueError("Введите ключи отбеливания K_pre и K_post")
+            whitening = tuple(fit_key(k, block_bytes(block_size)) for k in fields)
+        if whitening and structure is not None:
+            raise ValueError("Отбеливание поддерживается только классической сетью")
+        
+        def crypt_pass(block, key_data, decrypt):
+            if structure in GFN_TYPES:
+                return crypt_block_gfn(block, key_data, decrypt, rounds, branches, 
+                                       structure, round_func, schedule, tweak=tweak)
+            if structure == LAI_MASSEY:
+                return crypt_block_lai_massey(block, key_data, decrypt, rounds, 
+                                              round_func, schedule, tweak=tweak)
+            return crypt_block(block, key_data, decrypt, rounds, round_func, schedule, split, 
+                               ip=ip, fp=fp, whitening=whitening, tweak=tweak)
+        
+        # Ключи раундов, полученные по выбранному расписанию
+        keys_text = ''
+        for number, key_data in enumerate(keys_data, 1):
+            if structure in GFN_TYPES:
+                per_round = len(GFN_TYPES[structure](branches))
+                round_keys = keys_gen(key_data.copy(), False, rounds * per_round, 
+                                      block_bytes(block_size) // branches, schedule, tweak)
+            elif structure == LAI_MASSEY:
+                round_keys = keys_gen(key_data.copy(), False, rounds, block_bytes(block_size) // 2, 
+                                      schedule, tweak)
+            else:
+                round_keys = keys_gen(key_data.copy(), False, rounds, block_bytes(block_size) - split, 
+                                      schedule, tweak)
+            round_keys = '\n'.join([f"K{i+1}: {' '.join([f'{b:02x}' for b in k])}" 
+                                    for i, k in enumerate(round_keys)])
+            owner = f", ключ k{number}" if len(keys_data) > 1 else ""
+            keys_text += f"\nКлючи раундов ({self.schedule_input.currentText()}{owner}):\n{round_keys}"
+        if whitening:
+            keys_text += (f"\nK_pre: {' '.join([f'{b:02x}' for b in whitening[0]])}"
+                          f"\nK_post: {' '.join([f'{b:02x}' for b in whitening[1]])}")
+        
+        def make_visualizer(block, decrypt, pad_len):
+            return FeistelVisualizer(block, keys_data[0], rounds, decrypt, pad_len, round_func, 
+                                     schedule, split, structure, branches, ip, fp, whitening, tweak, 
+                                     cascade=keys_data[1:])
+        
+        return self.cascade_setup(crypt_pass, keys_data, keys_text) + (make_visualizer,)
+    
+    def cipher_changed(self):
+        """Включает поля настройки сети только для настраиваемого шифра"""
+        name = self.cipher_input.currentData()
+        for widget in self.custom_cipher_widgets:
+            widget.setEnabled(name is None)
+        if name is None:
+            self.key_input.setPlaceholderText("Введите ключ")
+        else:
+            self.key_input.setPlaceholderText(f"Ключ {name} в HEX")
+    
+    def cascade_changed(self):
+        """Включает поля ключей k2, k3 по числу ключей каскада"""
+        count = self.cascade_input.currentData() or 1
+        for number, field in enumerate(self.cascade_key_inputs, 2):
+            field.setEnabled(number <= count)
+    
+    def cipher_self_test(self):
+        """Проверяет выбранный стандартный шифр по известным ответам"""
+        name = self.cipher_input.currentData()
+        if name is None:
+            self.result_output.setText("Ошибка: Выберите стандартный шифр")
+            return
+        lines = run_test_vectors(CIPHERS[name]["crypt"], CIPHERS[name]["tests"])
+        self.result_output.setText(f"Известные ответы {name}:\n" + '\n'.join(lines))
 
 def main():
     """